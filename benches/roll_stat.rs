@@ -0,0 +1,66 @@
+//! Timings for the rolling hot path.
+//!
+//! Run with `cargo bench`. Every case uses the same seed, so runs
+//! are comparable before and after a change.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use arrata_lib::{roll_into, roll_stat_with_rng, Stat};
+use rand::{rngs::StdRng, SeedableRng};
+
+const SEED: u64 = 0x00A2_2A7A;
+const ITERATIONS: u32 = 200_000;
+
+fn main() {
+    let cases = [
+        ("small pool B3", "B3", 0, 0),
+        ("large pool S30", "S30", 0, 0),
+        ("heavy advantage !6A4", "A4", 6, 0),
+        ("advantage and disadvantage !3?3B6", "B6", 3, 3),
+    ];
+
+    for (label, stat, advantage, disadvantage) in cases {
+        let stat = Stat::from(stat.to_string());
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let elapsed = time(|| {
+            black_box(roll_stat_with_rng(
+                black_box(&stat),
+                advantage,
+                disadvantage,
+                &mut rng,
+            ));
+        });
+        report(label, "roll_stat_with_rng", elapsed);
+
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut buf = Vec::new();
+        let elapsed = time(|| {
+            black_box(roll_into(
+                black_box(&stat),
+                advantage,
+                disadvantage,
+                &mut buf,
+                &mut rng,
+            ));
+        });
+        report(label, "roll_into", elapsed);
+    }
+}
+
+fn time(mut roll: impl FnMut()) -> Duration {
+    // Warm up caches and the branch predictor first.
+    for _ in 0..ITERATIONS / 10 {
+        roll();
+    }
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        roll();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn report(label: &str, function: &str, per_roll: Duration) {
+    println!("{label:<36} {function:<20} {per_roll:>10.2?}/roll");
+}
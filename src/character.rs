@@ -3,43 +3,194 @@
 
 /* Structs and Enums */
 
+#[cfg(feature = "bitcode")]
+pub mod v0;
+#[cfg(feature = "bitcode")]
+pub mod v1;
+
+#[cfg(feature = "std")]
+use alloc::vec;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "bitcode")]
 use bitcode::{Decode, Encode};
 
-use crate::{Inspiration, Quirk};
+#[cfg(feature = "bitcode")]
+use crate::serialization::envelope::{
+    read_envelope, write_envelope, EnvelopeError, EnvelopeMeta, PayloadEncoding, HEADER_LEN, MAGIC,
+};
+#[cfg(any(feature = "bitcode", feature = "serde"))]
+use crate::ArrataError;
+use crate::{
+    markdown, Inspiration, InspirationEvent, InspirationField, InspirationNote, Obstacle,
+    ObstacleRule, Prerequisite, Quirk, QuirkBalanceReport, QuirkCategory, QuirkDiff,
+    QuirkMergeError, QuirkUsage, SessionQuirkReport, UnmetPrerequisite,
+};
+#[cfg(feature = "std")]
+use crate::{roll_stat, RollResult, SceneModifier};
 
 /// A struct containing all info about a character.
-#[derive(Encode, Decode, Serialize, Deserialize, Clone, PartialEq, Debug)]
+///
+/// JSON saves ignore fields they don't know, so a character saved by
+/// a newer version with extra fields still loads here, minus those
+/// fields. Missing fields fall back to their defaults.
+///
+/// Bitcode saves have none of that slack: the encoding isn't
+/// self-describing, so any change to these fields changes the layout.
+/// Older layouts can only be told apart by the version in their
+/// [envelope](crate::serialization::envelope), which must be bumped
+/// with every change.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(remote = "Self"))]
 pub struct Character {
-    #[serde(default = "default_name")]
+    /// The character's stable identity, independent of name.
+    ///
+    /// Set randomly by [`Character::new`]. Struct literals must set
+    /// it too, usually with `..Character::new(name)`. JSON saves made
+    /// before ids existed get one derived from their contents, so the
+    /// same save loads with the same id every time. An id of zero is
+    /// treated as missing.
+    #[cfg_attr(feature = "serde", serde(default, with = "id_format"))]
+    pub id: u128,
+    #[cfg_attr(feature = "serde", serde(default = "default_name"))]
     pub name: String,
-    #[serde(default = "default_stock")]
+    #[cfg_attr(feature = "serde", serde(default = "default_stock"))]
     pub stock: String,
-    #[serde(default = "default_stats")]
+    #[cfg_attr(feature = "serde", serde(default = "default_stats"))]
     pub stats: Vec<Stat>,
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub skills: Vec<Stat>,
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub resources: Vec<Resource>,
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub quirks: Vec<Quirk>,
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
     pub inspiration: Inspiration,
-    #[serde(default)]
-    pub argos: String,
-    #[serde(default)]
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub argos: Argos,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub inventory: Vec<Item>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub macros: Vec<RollMacro>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inspiration_log: Vec<InspirationEvent>,
+    /// Scene-scoped, so left out of every save, JSON or binary, and
+    /// of patches and fingerprints. Always empty when loaded.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub temp_effects: Vec<TempEffect>,
+}
+
+#[cfg(feature = "std")]
+fn new_id() -> u128 {
+    rand::random()
+}
+
+/// A 128-bit FNV-1a hash of `bytes`, used for ids derived from a
+/// save's contents. It never returns zero, which marks a missing id.
+#[cfg(any(feature = "serde", feature = "bitcode"))]
+fn fnv1a_id(bytes: &[u8]) -> u128 {
+    const OFFSET: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+    const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+    let mut hash = OFFSET;
+    for byte in bytes {
+        hash ^= u128::from(*byte);
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash.max(1)
+}
+
+/// An id for a save that has none, derived from the rest of its
+/// contents so it's the same on every load.
+///
+/// This is the [`fnv1a_id`] of the character's JSON with a zero id.
+#[cfg(feature = "serde")]
+fn content_id(character: &Character) -> u128 {
+    let json = serde_json::to_vec(&Character {
+        id: 0,
+        ..character.clone()
+    })
+    .unwrap_or_default();
+    fnv1a_id(&json)
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Character {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Character::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Character {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut character = Character::deserialize(deserializer)?;
+        if character.id == 0 {
+            character.id = content_id(&character);
+        }
+        Ok(character)
+    }
+}
+
+/// Serializes ids as 32 hex digits, since many JSON consumers
+/// can't represent a full `u128` as a number.
+#[cfg(feature = "serde")]
+mod id_format {
+    use serde::{de, Deserializer, Serializer};
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(id: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{id:032x}"))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        struct IdVisitor;
+
+        impl de::Visitor<'_> for IdVisitor {
+            type Value = u128;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "a hex string or an integer id")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<u128, E> {
+                let hex: String = v.chars().filter(|c| *c != '-').collect();
+                u128::from_str_radix(&hex, 16).map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<u128, E> {
+                Ok(u128::from(v))
+            }
+
+            fn visit_u128<E: de::Error>(self, v: u128) -> Result<u128, E> {
+                Ok(v)
+            }
+        }
+
+        deserializer.deserialize_any(IdVisitor)
+    }
 }
 
+#[cfg(feature = "serde")]
 fn default_name() -> String {
     "Name".to_string()
 }
 
+#[cfg(feature = "serde")]
 fn default_stock() -> String {
     "Stock".to_string()
 }
 
+#[cfg(feature = "serde")]
 fn default_stats() -> Vec<Stat> {
     vec![
         Stat::new("Will".into()),
@@ -51,10 +202,74 @@ fn default_stats() -> Vec<Stat> {
     ]
 }
 
+/// Rewrites a legacy character's JSON into the current layout, for
+/// [`Character::import_legacy`].
+#[cfg(feature = "serde")]
+fn migrate_legacy(
+    character: &mut serde_json::Map<String, serde_json::Value>,
+) -> Result<(), QualityParseError> {
+    use serde_json::{json, Map, Value};
+
+    type Migration = fn(&mut Map<String, Value>) -> Result<(), QualityParseError>;
+
+    fn each(
+        character: &mut Map<String, Value>,
+        field: &str,
+        migrate: Migration,
+    ) -> Result<(), QualityParseError> {
+        if let Some(Value::Array(entries)) = character.get_mut(field) {
+            for entry in entries.iter_mut().filter_map(Value::as_object_mut) {
+                entry.retain(|_, value| !value.is_null());
+                migrate(entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn stat(stat: &mut Map<String, Value>) -> Result<(), QualityParseError> {
+        let quality = match stat.get("quality") {
+            Some(Value::String(quality)) => quality.parse()?,
+            Some(_) => return Ok(()),
+            None => Quality::Basic,
+        };
+        stat.insert("quality".to_string(), json!(quality));
+        stat.entry("quantity").or_insert(json!(1));
+        Ok(())
+    }
+
+    character.retain(|_, value| !value.is_null());
+    each(character, "stats", stat)?;
+    each(character, "skills", stat)?;
+    each(character, "resources", |resource| {
+        if let Some(Value::Object(inner)) = resource.get_mut("stat") {
+            inner.retain(|_, value| !value.is_null());
+            stat(inner)?;
+        }
+        resource.entry("finite").or_insert(json!(false));
+        Ok(())
+    })?;
+    each(character, "quirks", |quirk| {
+        if !quirk.contains_key("type") {
+            quirk.entry("category").or_insert(json!("Uncategorized"));
+        }
+        quirk.entry("description").or_insert(json!(""));
+        quirk.entry("boons").or_insert(json!([]));
+        quirk.entry("flaws").or_insert(json!([]));
+        Ok(())
+    })?;
+    each(character, "inventory", |item| {
+        item.entry("quantity").or_insert(json!(0));
+        item.entry("description").or_insert(json!(""));
+        Ok(())
+    })
+}
+
 impl Character {
+    #[cfg(feature = "std")]
     #[must_use]
     pub fn new(name: String) -> Character {
         Character {
+            id: new_id(),
             name,
             stock: "Stock".to_string(),
             stats: vec![
@@ -69,156 +284,3295 @@ impl Character {
             quirks: Vec::new(),
             resources: Vec::new(),
             inspiration: Inspiration::new(),
-            argos: String::new(),
+            argos: Argos::new(),
             inventory: Vec::new(),
+            macros: Vec::new(),
+            inspiration_log: Vec::new(),
+            temp_effects: Vec::new(),
         }
     }
-}
 
-impl Default for Character {
-    fn default() -> Self {
-        Character {
-            name: "Name".to_string(),
-            stock: "Stock".to_string(),
-            stats: vec![
-                Stat::new("Will".into()),
-                Stat::new("Perception".into()),
-                Stat::new("Conscious".into()),
-                Stat::new("Power".into()),
-                Stat::new("Speed".into()),
-                Stat::new("Forte".into()),
-            ],
-            skills: Vec::new(),
-            quirks: Vec::new(),
-            resources: Vec::new(),
-            inspiration: Inspiration::new(),
-            argos: String::new(),
-            inventory: Vec::new(),
+    /// Builds a character from its parts, checking it with
+    /// [`Character::validate`] before handing it back.
+    ///
+    /// Everything not given starts as in [`Character::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ValidationError`] listing every problem found.
+    #[cfg(feature = "std")]
+    pub fn from_parts(
+        name: String,
+        stock: String,
+        stats: Vec<Stat>,
+        skills: Vec<Stat>,
+        quirks: Vec<Quirk>,
+        inventory: Vec<Item>,
+    ) -> Result<Character, ValidationError> {
+        let character = Character {
+            stock,
+            stats,
+            skills,
+            quirks,
+            inventory,
+            ..Character::new(name)
+        };
+        let issues = character.validate();
+        if issues.is_empty() {
+            Ok(character)
+        } else {
+            Err(ValidationError { issues })
         }
     }
-}
 
-/// A struct for Stats.
-///
-/// `checks` is optional as some stats don't
-/// require checks to function.
-#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct Stat {
-    pub name: String,
-    pub quality: Quality,
-    pub quantity: usize,
-    pub checks: Option<usize>,
-}
+    /// The character's stable identity, independent of name.
+    ///
+    /// See the `id` field for how it's set.
+    #[must_use]
+    pub fn id(&self) -> u128 {
+        self.id
+    }
 
-impl Stat {
+    /// Finds a stat or skill by name, ignoring case.
+    ///
+    /// Stats are searched before skills.
     #[must_use]
-    pub fn new(name: String) -> Stat {
-        Stat {
-            name,
-            quality: Quality::Basic,
-            quantity: 1,
-            checks: Some(0),
+    pub fn find_stat(&self, name: &str) -> Option<&Stat> {
+        self.stats
+            .iter()
+            .chain(self.skills.iter())
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a stat or skill by name mutably, ignoring case.
+    ///
+    /// Stats are searched before skills.
+    pub fn find_stat_mut(&mut self, name: &str) -> Option<&mut Stat> {
+        self.stats
+            .iter_mut()
+            .chain(self.skills.iter_mut())
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the stat at `index`, if any.
+    #[must_use]
+    pub fn stat_at(&self, index: usize) -> Option<&Stat> {
+        self.stats.get(index)
+    }
+
+    /// Returns the stat at `index` mutably, if any.
+    pub fn stat_at_mut(&mut self, index: usize) -> Option<&mut Stat> {
+        self.stats.get_mut(index)
+    }
+
+    /// Finds a stat by name, ignoring case.
+    #[must_use]
+    pub fn stat(&self, name: &str) -> Option<&Stat> {
+        self.stats
+            .iter()
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a stat by name mutably, ignoring case.
+    pub fn stat_mut(&mut self, name: &str) -> Option<&mut Stat> {
+        self.stats
+            .iter_mut()
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the skill at `index`, if any.
+    #[must_use]
+    pub fn skill_at(&self, index: usize) -> Option<&Stat> {
+        self.skills.get(index)
+    }
+
+    /// Returns the skill at `index` mutably, if any.
+    pub fn skill_at_mut(&mut self, index: usize) -> Option<&mut Stat> {
+        self.skills.get_mut(index)
+    }
+
+    /// Finds a skill by name, ignoring case.
+    #[must_use]
+    pub fn skill(&self, name: &str) -> Option<&Stat> {
+        self.skills
+            .iter()
+            .find(|skill| skill.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a skill by name mutably, ignoring case.
+    pub fn skill_mut(&mut self, name: &str) -> Option<&mut Stat> {
+        self.skills
+            .iter_mut()
+            .find(|skill| skill.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the resource at `index`, if any.
+    #[must_use]
+    pub fn resource_at(&self, index: usize) -> Option<&Resource> {
+        self.resources.get(index)
+    }
+
+    /// Returns the resource at `index` mutably, if any.
+    pub fn resource_at_mut(&mut self, index: usize) -> Option<&mut Resource> {
+        self.resources.get_mut(index)
+    }
+
+    /// Finds a resource by name, ignoring case.
+    #[must_use]
+    pub fn resource(&self, name: &str) -> Option<&Resource> {
+        self.resources
+            .iter()
+            .find(|resource| resource.stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a resource by name mutably, ignoring case.
+    pub fn resource_mut(&mut self, name: &str) -> Option<&mut Resource> {
+        self.resources
+            .iter_mut()
+            .find(|resource| resource.stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the quirk at `index`, if any.
+    #[must_use]
+    pub fn quirk_at(&self, index: usize) -> Option<&Quirk> {
+        self.quirks.get(index)
+    }
+
+    /// Returns the quirk at `index` mutably, if any.
+    pub fn quirk_at_mut(&mut self, index: usize) -> Option<&mut Quirk> {
+        self.quirks.get_mut(index)
+    }
+
+    /// Finds a quirk by name, ignoring case.
+    #[must_use]
+    pub fn quirk(&self, name: &str) -> Option<&Quirk> {
+        self.quirks
+            .iter()
+            .find(|quirk| quirk.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a quirk by name mutably, ignoring case.
+    pub fn quirk_mut(&mut self, name: &str) -> Option<&mut Quirk> {
+        self.quirks
+            .iter_mut()
+            .find(|quirk| quirk.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns the item at `index`, if any.
+    #[must_use]
+    pub fn item_at(&self, index: usize) -> Option<&Item> {
+        self.inventory.get(index)
+    }
+
+    /// Returns the item at `index` mutably, if any.
+    pub fn item_at_mut(&mut self, index: usize) -> Option<&mut Item> {
+        self.inventory.get_mut(index)
+    }
+
+    /// Finds a item by name, ignoring case.
+    #[must_use]
+    pub fn item(&self, name: &str) -> Option<&Item> {
+        self.inventory
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a item by name mutably, ignoring case.
+    pub fn item_mut(&mut self, name: &str) -> Option<&mut Item> {
+        self.inventory
+            .iter_mut()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Adds an item to the inventory, topping up existing stacks
+    /// with the same name, ignoring case, before starting new ones.
+    ///
+    /// Existing stacks fill up to their own `max_stack`, and
+    /// anything left over goes into new stacks no larger than the
+    /// added item's `max_stack`.
+    ///
+    /// Returns how many couldn't be added, which only happens for
+    /// an item with a `max_stack` of zero.
+    pub fn add_item(&mut self, item: Item) -> usize {
+        let mut remaining = item.quantity;
+        let mut stacked = false;
+        for stack in self
+            .inventory
+            .iter_mut()
+            .filter(|stack| stack.name.eq_ignore_ascii_case(&item.name))
+        {
+            stacked = true;
+            remaining = stack.add(remaining);
+        }
+        if stacked && remaining == 0 {
+            return 0;
+        }
+        if item.max_stack == Some(0) {
+            return remaining;
+        }
+
+        let template = Item {
+            quantity: 0,
+            ..item
+        };
+        loop {
+            let mut stack = template.clone();
+            remaining = stack.add(remaining);
+            self.inventory.push(stack);
+            if remaining == 0 {
+                return 0;
+            }
         }
     }
-}
 
-impl From<String> for Stat {
-    /// Given in the form `{Quality}{Quantity}`.
-    /// No `name` or `checks` field are accepted.
-    fn from(value: String) -> Self {
-        if let Some(first_char) = value.chars().next() {
-            let quality = match first_char {
-                'A' | 'a' => Quality::Adept,
-                'S' | 's' => Quality::Superb,
-                _ => Quality::Basic,
-            };
-            let quantity = value[1..].parse::<usize>().unwrap_or(1);
-            Stat {
-                name: String::new(),
-                quality,
-                quantity,
-                checks: Some(0),
+    /// Rolls the macro with the given name, ignoring case.
+    ///
+    /// The macro's advantage and disadvantage stack on the
+    /// character's own modifiers, as [`Character::roll_modified`].
+    ///
+    /// Returns `None` if there is no such macro or if
+    /// the stat it refers to doesn't exist.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn run_macro(&self, name: &str) -> Option<RollResult> {
+        let roll_macro = self
+            .macros
+            .iter()
+            .find(|roll_macro| roll_macro.name.eq_ignore_ascii_case(name))?;
+        self.roll_modified(
+            &roll_macro.stat,
+            roll_macro.advantage,
+            roll_macro.disadvantage,
+        )
+    }
+
+    /// Rolls a stat or skill by name with everything the character
+    /// brings to it, on top of `advantage` and `disadvantage`.
+    ///
+    /// The stat is rolled as [`Character::passive_stat`] with active
+    /// temporary effects applied, and with the advantage and
+    /// disadvantage from [`Character::roll_modifiers`] stacked on.
+    ///
+    /// Returns `None` if there is no such stat or skill.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn roll_modified(
+        &self,
+        name: &str,
+        advantage: usize,
+        disadvantage: usize,
+    ) -> Option<RollResult> {
+        let stat = self.rolling_stat(name)?;
+        let (quirk_advantage, quirk_disadvantage) = self.roll_modifiers(name);
+        Some(roll_stat(
+            &stat,
+            advantage.saturating_add(quirk_advantage),
+            disadvantage.saturating_add(quirk_disadvantage),
+        ))
+    }
+
+    /// Rolls a stat or skill by name with a scene's modifier applied.
+    ///
+    /// The scene's levels stack on top of the character's own, as
+    /// [`Character::roll_modified`]. See [`SceneModifier`] for how
+    /// modifiers stack.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn roll_in_scene(&self, stat: &str, scene: &SceneModifier) -> Option<RollResult> {
+        let (quirk_advantage, quirk_disadvantage) = self.roll_modifiers(stat);
+        let (advantage, disadvantage) = scene.stack(quirk_advantage, quirk_disadvantage);
+        Some(roll_stat(
+            &self.rolling_stat(stat)?,
+            advantage,
+            disadvantage,
+        ))
+    }
+
+    /// The advantage and disadvantage the character's quirks give
+    /// rolls of a stat or skill, summed separately.
+    ///
+    /// Conditions are decided as in [`Character::passive_stat`], so
+    /// "advantage on Forte while carrying a shield" counts when the
+    /// character holds a shield, and item-conditioned modifiers are
+    /// how items affect rolls.
+    #[must_use]
+    pub fn roll_modifiers(&self, name: &str) -> (usize, usize) {
+        self.quirks
+            .iter()
+            .flat_map(Quirk::passive_modifiers)
+            .filter(|modifier| modifier.stat.eq_ignore_ascii_case(name))
+            .filter(|modifier| {
+                modifier
+                    .condition
+                    .as_deref()
+                    .is_none_or(|condition| self.carries_for(condition))
+            })
+            .fold((0, 0), |(advantage, disadvantage), modifier| {
+                (
+                    advantage.saturating_add(modifier.advantage),
+                    disadvantage.saturating_add(modifier.disadvantage),
+                )
+            })
+    }
+
+    /// The stat as rolled: passive modifiers, then temporary effects.
+    #[cfg(feature = "std")]
+    fn rolling_stat(&self, name: &str) -> Option<Stat> {
+        Some(self.with_temps(self.passive_stat(name)?))
+    }
+
+    /// Checks a quirk's prerequisites against this character.
+    ///
+    /// Returns every prerequisite that isn't met.
+    #[must_use]
+    pub fn check_quirk_prerequisites(&self, quirk: &Quirk) -> Vec<UnmetPrerequisite> {
+        quirk
+            .prerequisites
+            .iter()
+            .filter(|prerequisite| !self.meets(prerequisite))
+            .map(|prerequisite| UnmetPrerequisite {
+                prerequisite: prerequisite.clone(),
+            })
+            .collect()
+    }
+
+    fn meets(&self, prerequisite: &Prerequisite) -> bool {
+        match prerequisite {
+            Prerequisite::MinStat(name, quality, quantity) => self
+                .find_stat(name)
+                .is_some_and(|stat| stat.meets(*quality, *quantity)),
+            Prerequisite::Stock(stock) => self.stock.eq_ignore_ascii_case(stock),
+            Prerequisite::HasQuirk(name) => self
+                .quirks
+                .iter()
+                .any(|quirk| quirk.name.eq_ignore_ascii_case(name)),
+        }
+    }
+
+    /// Adds a quirk to the character.
+    ///
+    /// If `enforce_prerequisites` is set, the quirk is only added
+    /// when all of its prerequisites are met.
+    ///
+    /// # Errors
+    ///
+    /// Returns the unmet prerequisites if they're enforced and
+    /// any aren't met.
+    pub fn add_quirk(
+        &mut self,
+        quirk: Quirk,
+        enforce_prerequisites: bool,
+    ) -> Result<(), Vec<UnmetPrerequisite>> {
+        if enforce_prerequisites {
+            let unmet = self.check_quirk_prerequisites(&quirk);
+            if !unmet.is_empty() {
+                return Err(unmet);
             }
+        }
+        self.quirks.push(quirk);
+        Ok(())
+    }
+
+    /// Adds a quirk to the character, checking its prerequisites
+    /// and the given quirk limits first.
+    ///
+    /// Only the limits the new quirk counts toward are checked: the
+    /// total, and `per_category` for its own category. A character
+    /// already over the limit in another category can still take it.
+    ///
+    /// # Errors
+    ///
+    /// Returns every issue adding the quirk would cause, in which
+    /// case the quirk is not added.
+    pub fn add_quirk_checked(
+        &mut self,
+        quirk: Quirk,
+        limits: &QuirkLimits,
+    ) -> Result<(), Vec<ValidationIssue>> {
+        let mut issues: Vec<ValidationIssue> = self
+            .check_quirk_prerequisites(&quirk)
+            .into_iter()
+            .map(|unmet| ValidationIssue::UnmetPrerequisite {
+                quirk: quirk.name.clone(),
+                unmet,
+            })
+            .collect();
+
+        let category = quirk.category.clone();
+        self.quirks.push(quirk);
+        issues.extend(limits.check_categories(&self.quirks, |other| *other == category));
+        if issues.is_empty() {
+            Ok(())
         } else {
-            Stat::new(String::new())
+            self.quirks.pop();
+            Err(issues)
         }
     }
-}
 
-impl std::fmt::Display for Stat {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.quality {
-            Quality::Basic => write!(f, "B{}", self.quantity),
-            Quality::Adept => write!(f, "A{}", self.quantity),
-            Quality::Superb => write!(f, "S{}", self.quantity),
+    /// Checks the character for problems.
+    ///
+    /// Everything reported here is a warning rather than a hard
+    /// error, since GMs are free to override the rules. Re-run it
+    /// after changing stats, as quirks whose prerequisites were met
+    /// when taken may no longer be.
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        self.validate_with(&QuirkLimits::default())
+    }
+
+    /// Checks the character for problems, including the given
+    /// quirk limits.
+    ///
+    /// See [`Character::validate`].
+    #[must_use]
+    pub fn validate_with(&self, limits: &QuirkLimits) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if self.name.trim().is_empty() {
+            issues.push(ValidationIssue::EmptyName);
+        }
+        let all_stats: Vec<&Stat> = self.stats.iter().chain(&self.skills).collect();
+        for (i, stat) in all_stats.iter().enumerate() {
+            let same_name = |other: &&Stat| other.name.eq_ignore_ascii_case(&stat.name);
+            // Report each name once, at its first appearance.
+            if !all_stats[..i].iter().any(same_name) && all_stats[i + 1..].iter().any(same_name) {
+                issues.push(ValidationIssue::DuplicateStat(stat.name.clone()));
+            }
         }
+
+        issues.extend(self.quirks.iter().flat_map(|quirk| {
+            self.check_quirk_prerequisites(quirk)
+                .into_iter()
+                .map(|unmet| ValidationIssue::UnmetPrerequisite {
+                    quirk: quirk.name.clone(),
+                    unmet,
+                })
+        }));
+        issues.extend(limits.check(&self.quirks));
+        issues
     }
-}
 
-/// An abstraction for resources.
-/// 
-/// Effectively a stat with a boolean defining finite/infinite status.
-#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, PartialEq)]
-pub struct Resource {
-    /// The stat associated with the resource.
-    pub stat: Stat,
-    /// Whether the resource is finite.
-    pub finite: bool,
-}
+    /// Awards inspiration in a category.
+    ///
+    /// If a note is given, the award is recorded in the
+    /// character's `inspiration_log`.
+    ///
+    /// Returns `false` if the category has no inspiration pool.
+    pub fn award_inspiration(
+        &mut self,
+        category: &QuirkCategory,
+        amount: usize,
+        note: Option<InspirationNote>,
+    ) -> bool {
+        let Some(pool) = self.inspiration.get_mut(category) else {
+            return false;
+        };
+        *pool = pool.saturating_add(amount);
+        self.log_inspiration(
+            category,
+            isize::try_from(amount).unwrap_or(isize::MAX),
+            note,
+        );
+        true
+    }
 
-impl Resource {
+    /// Spends inspiration in a category.
+    ///
+    /// If a note is given, the spend is recorded in the
+    /// character's `inspiration_log`.
+    ///
+    /// Returns `false`, spending nothing, if the category has no
+    /// inspiration pool or not enough inspiration.
+    pub fn spend_inspiration(
+        &mut self,
+        category: &QuirkCategory,
+        amount: usize,
+        note: Option<InspirationNote>,
+    ) -> bool {
+        let Some(pool) = self.inspiration.get_mut(category) else {
+            return false;
+        };
+        let Some(remaining) = pool.checked_sub(amount) else {
+            return false;
+        };
+        *pool = remaining;
+        self.log_inspiration(
+            category,
+            isize::try_from(amount).map_or(isize::MIN, |amount| -amount),
+            note,
+        );
+        true
+    }
+
+    fn log_inspiration(
+        &mut self,
+        category: &QuirkCategory,
+        delta: isize,
+        note: Option<InspirationNote>,
+    ) {
+        if let Some(note) = note {
+            self.inspiration_log.push(InspirationEvent {
+                category: category.clone(),
+                delta,
+                reason: note.reason,
+                session: note.session,
+            });
+        }
+    }
+
+    /// The logged inspiration events for a category, oldest first.
+    pub fn inspiration_history<'a>(
+        &'a self,
+        category: &'a QuirkCategory,
+    ) -> impl Iterator<Item = &'a InspirationEvent> + 'a {
+        self.inspiration_log
+            .iter()
+            .filter(move |event| &event.category == category)
+    }
+
+    /// Returns a stat or skill by name with all active
+    /// temporary effects applied, leaving the base stat untouched.
     #[must_use]
-    pub fn new(stat: Stat, finite: bool) -> Self {
-        Self { stat, finite }
+    pub fn effective_stat_with_temps(&self, name: &str) -> Option<Stat> {
+        Some(self.with_temps(self.find_stat(name)?.clone()))
     }
-}
 
-impl Default for Resource {
-    fn default() -> Self {
-        Self::new(Stat::new("New Resource!".into()), false)
+    /// Applies the active temporary effects on `stat`.
+    fn with_temps(&self, mut stat: Stat) -> Stat {
+        for effect in self
+            .temp_effects
+            .iter()
+            .filter(|effect| effect.is_active() && effect.stat.eq_ignore_ascii_case(&stat.name))
+        {
+            stat.quality = stat.quality.shifted(effect.quality_shift);
+            stat.quantity = stat.quantity.saturating_add_signed(effect.quantity_delta);
+        }
+        stat
     }
-}
 
-/// A struct for Quality. Determines the
-/// lower bound for rolls.
-#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
-pub enum Quality {
-    Basic = 4,
-    Adept = 3,
-    Superb = 2,
-}
+    /// Returns a stat or skill by name with every passive quirk
+    /// modifier that applies, leaving the base stat untouched.
+    ///
+    /// Unconditional modifiers always apply. Conditions about carrying
+    /// an item hold if the inventory has at least one; any other
+    /// condition doesn't. See [`crate::PassiveModifier`] for the
+    /// grammar, and [`Character::passive_stat_with`] to evaluate
+    /// conditions yourself.
+    #[must_use]
+    pub fn passive_stat(&self, name: &str) -> Option<Stat> {
+        self.passive_stat_with(name, |condition| self.carries_for(condition))
+    }
 
-impl std::fmt::Display for Quality {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Quality::Basic => write!(f, "Basic"),
-            Quality::Adept => write!(f, "Adept"),
-            Quality::Superb => write!(f, "Superb"),
+    /// Like [`Character::passive_stat`], with `holds` deciding whether
+    /// each condition is currently true.
+    pub fn passive_stat_with(
+        &self,
+        name: &str,
+        mut holds: impl FnMut(&str) -> bool,
+    ) -> Option<Stat> {
+        let mut stat = self.find_stat(name)?.clone();
+        for modifier in self
+            .quirks
+            .iter()
+            .flat_map(Quirk::passive_modifiers)
+            .filter(|modifier| modifier.stat.eq_ignore_ascii_case(name))
+        {
+            if modifier.condition.as_deref().is_some_and(|c| !holds(c)) {
+                continue;
+            }
+            stat.quality = stat.quality.shifted(modifier.quality_shift);
+            stat.quantity = stat.quantity.saturating_add_signed(modifier.quantity_delta);
         }
+        Some(stat)
     }
-}
 
-/// A struct for items.
-#[derive(Encode, Decode, Serialize, Deserialize, Clone, PartialEq, Debug)]
-pub struct Item {
-    pub name: String,
-    pub quantity: usize,
-    pub description: String,
-}
+    /// Whether a condition like "you carry a shield" holds.
+    fn carries_for(&self, condition: &str) -> bool {
+        let lower = condition.to_ascii_lowercase();
+        let Some(item) = ["you carry ", "carrying ", "you have "]
+            .iter()
+            .find_map(|prefix| lower.strip_prefix(prefix))
+        else {
+            return false;
+        };
+        let item = ["a ", "an ", "the "]
+            .iter()
+            .find_map(|article| item.strip_prefix(article))
+            .unwrap_or(item)
+            .trim();
+        self.inventory
+            .iter()
+            .any(|held| held.quantity > 0 && held.name.eq_ignore_ascii_case(item))
+    }
 
-impl Item {
+    /// Counts down every temporary effect by one,
+    /// dropping those that have expired.
+    pub fn tick_temps(&mut self) {
+        for effect in &mut self.temp_effects {
+            effect.expires_in = effect.expires_in.saturating_sub(1);
+        }
+        self.temp_effects.retain(TempEffect::is_active);
+    }
+
+    /// Summarizes the boon/flaw balance of every quirk.
+    ///
+    /// See [`Quirk::balance`].
     #[must_use]
-    pub fn new(name: String) -> Self {
-        Self {
-            name,
-            quantity: 0,
-            description: String::new(),
+    pub fn quirk_balance_report(&self) -> QuirkBalanceReport {
+        QuirkBalanceReport {
+            quirks: self.quirks.iter().map(Quirk::balance).collect(),
         }
     }
-}
 
-impl Default for Item {
-    fn default() -> Self {
-        Self::new("New Item!".into())
+    /// Merges duplicate quirks into their first occurrence, with
+    /// [`Quirk::merge`].
+    ///
+    /// Quirks are duplicates if their names match, ignoring case.
+    /// Returns how many quirks were merged away.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`QuirkMergeError::CategoryMismatch`] for the first
+    /// same-named pair in different categories, rather than guessing
+    /// which is right. The quirks are left untouched.
+    pub fn dedup_quirks(&mut self) -> Result<usize, QuirkMergeError> {
+        for (index, quirk) in self.quirks.iter().enumerate() {
+            if let Some(first) = self.quirks[..index].iter().find(|first| {
+                first.name.eq_ignore_ascii_case(&quirk.name) && first.category != quirk.category
+            }) {
+                return Err(QuirkMergeError::CategoryMismatch(
+                    first.category.clone(),
+                    quirk.category.clone(),
+                ));
+            }
+        }
+
+        let mut kept: Vec<Quirk> = Vec::with_capacity(self.quirks.len());
+        let mut merged = 0;
+        for quirk in core::mem::take(&mut self.quirks) {
+            match kept
+                .iter_mut()
+                .find(|kept| kept.name.eq_ignore_ascii_case(&quirk.name))
+            {
+                Some(existing) => {
+                    existing.merge(quirk)?;
+                    merged += 1;
+                }
+                None => kept.push(quirk),
+            }
+        }
+        self.quirks = kept;
+        Ok(merged)
+    }
+
+    /// The character's quirks with a tag, ignoring case.
+    pub fn quirks_with_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Quirk> + 'a {
+        self.quirks.iter().filter(move |quirk| quirk.has_tag(tag))
+    }
+
+    /// How many quirks the character has in each category, in the
+    /// order of [`QuirkCategory::ALL`].
+    #[must_use]
+    pub fn quirk_category_counts(&self) -> [usize; QuirkCategory::ALL.len()] {
+        QuirkCategory::ALL.map(|category| {
+            self.quirks
+                .iter()
+                .filter(|quirk| quirk.category == category)
+                .count()
+        })
+    }
+
+    /// Formats the character's quirks as markdown, grouped under
+    /// a heading per category in display order.
+    ///
+    /// See [`Quirk::to_markdown`]. Quirks whose anchors would repeat
+    /// get `-2`, `-3`, and so on, like GitHub's heading links.
+    #[must_use]
+    pub fn quirks_to_markdown(&self) -> String {
+        self.quirks_markdown(2)
+    }
+
+    /// The quirks with category headings at `level` and each quirk
+    /// one level below.
+    fn quirks_markdown(&self, level: u8) -> String {
+        let quirks = self.sorted_quirks();
+        let mut out = String::new();
+        let mut current = None;
+        let mut anchors = markdown::Anchors::default();
+        for quirk in &quirks {
+            if current != Some(&quirk.category) {
+                if !out.is_empty() {
+                    out.push('\n');
+                }
+                out.push_str(&markdown::heading(level));
+                out.push(' ');
+                out.push_str(&quirk.category.to_string());
+                out.push('\n');
+                current = Some(&quirk.category);
+            }
+            out.push('\n');
+            out.push_str(&quirk.to_markdown_anchored(level + 1, &anchors.claim(&quirk.anchor())));
+        }
+        out
+    }
+
+    /// Formats the whole sheet as a markdown page for wikis
+    /// and other documentation pipelines.
+    ///
+    /// The name is the title, followed by sections for stats, skills,
+    /// resources, inspiration, quirks, inventory, and argos. Empty
+    /// sections other than stats are left out.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# ");
+        out.push_str(&markdown::escape(&self.name));
+        out.push_str("\n\n*");
+        out.push_str(&markdown::escape(&self.stock));
+        out.push_str("*\n\n## Stats\n\n| Stat | Value | Checks |\n| --- | --- | --- |\n");
+        for stat in &self.stats {
+            push_stat_row(&mut out, stat);
+        }
+
+        if !self.skills.is_empty() {
+            out.push_str("\n## Skills\n\n| Skill | Value | Checks |\n| --- | --- | --- |\n");
+            for skill in &self.skills {
+                push_stat_row(&mut out, skill);
+            }
+        }
+
+        if !self.resources.is_empty() {
+            out.push_str("\n## Resources\n\n");
+            for resource in &self.resources {
+                out.push_str("- **");
+                out.push_str(&markdown::escape(&resource.stat.name));
+                out.push_str("** ");
+                out.push_str(&resource.stat.to_string());
+                if resource.finite {
+                    out.push_str(" (finite)");
+                }
+                out.push('\n');
+            }
+        }
+
+        out.push_str("\n## Inspiration\n\n");
+        for field in InspirationField::ALL {
+            out.push_str("- ");
+            out.push_str(&field.category().to_string());
+            out.push_str(": ");
+            out.push_str(&self.inspiration.field(field).to_string());
+            out.push('\n');
+        }
+
+        if !self.quirks.is_empty() {
+            out.push_str("\n## Quirks\n\n");
+            out.push_str(&self.quirks_markdown(3));
+        }
+
+        if !self.inventory.is_empty() {
+            out.push_str(
+                "\n## Inventory\n\n| Item | Quantity | Description |\n| --- | --- | --- |\n",
+            );
+            for item in &self.inventory {
+                out.push_str("| ");
+                out.push_str(&markdown::escape(&item.name));
+                out.push_str(" | ");
+                out.push_str(&item.quantity.to_string());
+                out.push_str(" | ");
+                out.push_str(&markdown::escape(&item.description.replace('\n', " ")));
+                out.push_str(" |\n");
+            }
+        }
+
+        if !self.argos.is_empty() {
+            out.push_str("\n## Argos\n\n");
+            for entry in self.argos.iter() {
+                out.push_str("- ");
+                out.push_str(&markdown::escape(entry));
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    /// Records that a quirk drove play this session, with an
+    /// optional note on how.
+    ///
+    /// Returns the quirk's uses so far this session.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CharacterError::QuirkNotFound`] if the character
+    /// has no quirk with that name, ignoring case.
+    pub fn record_quirk_use(
+        &mut self,
+        name: &str,
+        note: Option<String>,
+    ) -> Result<usize, CharacterError> {
+        let quirk = self
+            .quirk_mut(name)
+            .ok_or_else(|| CharacterError::QuirkNotFound(name.to_string()))?;
+        quirk.uses_this_session += 1;
+        quirk.session_notes.extend(note);
+        Ok(quirk.uses_this_session)
+    }
+
+    /// Replaces the quirk with the same name, ignoring case, with
+    /// `updated` and returns what changed. The quirk's session uses
+    /// and notes carry over.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CharacterError::QuirkNotFound`] if the character
+    /// has no quirk with that name.
+    pub fn apply_quirk_update(&mut self, mut updated: Quirk) -> Result<QuirkDiff, CharacterError> {
+        let quirk = self
+            .quirk_mut(&updated.name)
+            .ok_or_else(|| CharacterError::QuirkNotFound(updated.name.clone()))?;
+        let diff = quirk.diff(&updated);
+        updated.uses_this_session = quirk.uses_this_session;
+        updated.session_notes = core::mem::take(&mut quirk.session_notes);
+        *quirk = updated;
+        Ok(diff)
+    }
+
+    /// Clears every quirk's session uses and notes, for the end
+    /// of a session. Nothing else on the sheet is touched.
+    pub fn reset_session_counters(&mut self) {
+        for quirk in &mut self.quirks {
+            quirk.uses_this_session = 0;
+            quirk.session_notes.clear();
+        }
+    }
+
+    /// Summarizes how often each quirk was used this session.
+    #[must_use]
+    pub fn session_quirk_report(&self) -> SessionQuirkReport {
+        let mut quirks: Vec<QuirkUsage> = self
+            .quirks
+            .iter()
+            .map(|quirk| QuirkUsage {
+                name: quirk.name.clone(),
+                uses: quirk.uses_this_session,
+                notes: quirk.session_notes.clone(),
+            })
+            .collect();
+        quirks.sort_by_key(|usage| core::cmp::Reverse(usage.uses));
+        SessionQuirkReport { quirks }
+    }
+
+    /// Records a use of a stat or skill, adding a check and
+    /// advancing it once it has enough checks: [`CHECKS_TO_ADVANCE`]
+    /// for one of the core stats, or [`SKILL_CHECKS_TO_ADVANCE`] for
+    /// a skill.
+    ///
+    /// Returns whether the stat advanced. Stats with `checks: None`
+    /// are left untouched and return `false`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CharacterError::StatNotFound`] if the character
+    /// has no stat or skill with that name, ignoring case.
+    pub fn record_use(&mut self, name: &str) -> Result<bool, CharacterError> {
+        let threshold = if self.stat(name).is_some() {
+            CHECKS_TO_ADVANCE
+        } else {
+            SKILL_CHECKS_TO_ADVANCE
+        };
+        let stat = self
+            .find_stat_mut(name)
+            .ok_or_else(|| CharacterError::StatNotFound(name.to_string()))?;
+        Ok(stat.add_check() && stat.try_advance(threshold))
+    }
+
+    /// Adds a check to a stat or skill only if the obstacle was hard
+    /// enough to count, per [`advancement_eligible`].
+    ///
+    /// Returns whether a check was added. GMs who want to count the
+    /// check anyway can call [`Stat::add_check`] directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CharacterError::StatNotFound`] if the character
+    /// has no stat or skill with that name, ignoring case.
+    pub fn record_check_if_eligible(
+        &mut self,
+        name: &str,
+        obstacle: &Obstacle,
+    ) -> Result<bool, CharacterError> {
+        self.record_check_if_eligible_with(name, obstacle, ELIGIBILITY_RULE)
+    }
+
+    /// [`Character::record_check_if_eligible`] under a different
+    /// eligibility rule, per [`advancement_eligible_with`], for hacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CharacterError::StatNotFound`] if the character
+    /// has no stat or skill with that name, ignoring case.
+    pub fn record_check_if_eligible_with(
+        &mut self,
+        name: &str,
+        obstacle: &Obstacle,
+        rule: ObstacleRule,
+    ) -> Result<bool, CharacterError> {
+        let stat = self
+            .find_stat_mut(name)
+            .ok_or_else(|| CharacterError::StatNotFound(name.to_string()))?;
+        Ok(advancement_eligible_with(stat, obstacle, rule) && stat.add_check())
+    }
+
+    /// A rough measure of how capable the character is, for comparing
+    /// characters at a glance.
+    ///
+    /// Each stat and skill adds its quantity weighted by quality:
+    /// one per die at Basic, two at Adept, and three at Superb. So B4
+    /// adds 4 and S4 adds 12.
+    #[must_use]
+    pub fn power_level(&self) -> usize {
+        self.stats
+            .iter()
+            .chain(&self.skills)
+            .map(|stat| {
+                let weight = match stat.quality {
+                    Quality::Basic => 1,
+                    Quality::Adept => 2,
+                    Quality::Superb => 3,
+                };
+                stat.quantity.saturating_mul(weight)
+            })
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Saves the character as bitcode inside a versioned
+    /// [envelope](crate::serialization::envelope).
+    ///
+    /// The result is much smaller than JSON. Like the JSON save, it
+    /// leaves out `temp_effects`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EnvelopeError::PayloadTooLarge`] if the encoded
+    /// character is over 4 GiB.
+    #[cfg(feature = "bitcode")]
+    pub fn to_envelope_bytes(&self) -> Result<Vec<u8>, EnvelopeError> {
+        let payload = self.encode_saved();
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        write_envelope(
+            &mut bytes,
+            EnvelopeMeta::new(PayloadEncoding::Bitcode),
+            &payload,
+        )?;
+        Ok(bytes)
+    }
+
+    /// Loads a character saved by [`Character::to_envelope_bytes`],
+    /// by this or any earlier version of the crate.
+    ///
+    /// Bitcode payloads are decoded with the layout for their
+    /// envelope's version, like [`v1::Character`], and converted
+    /// forward. JSON payloads are also read when the `serde` feature
+    /// is on, whatever their version.
+    ///
+    /// Bare bitcode saved before envelopes existed also loads, with
+    /// the [`v0`] layouts. Those saves had no id, so one is derived
+    /// from their bytes, the same on every load.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ArrataError::Envelope`] if the envelope is
+    /// malformed or corrupt, or has bytes after its payload, and an
+    /// [`ArrataError::Bitcode`] or `ArrataError::Json` if the
+    /// payload doesn't decode. Data that is neither an envelope nor
+    /// an old bare save is an [`EnvelopeError::BadMagic`].
+    #[cfg(feature = "bitcode")]
+    pub fn from_envelope_bytes(bytes: &[u8]) -> Result<Character, ArrataError> {
+        if !bytes.starts_with(&MAGIC) {
+            if let Some(character) = Self::from_bare_bitcode(bytes) {
+                return Ok(character);
+            }
+            if let Some(magic) = bytes.first_chunk() {
+                return Err(EnvelopeError::BadMagic(*magic).into());
+            }
+        }
+        let mut reader = bytes;
+        let (meta, payload) = read_envelope(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(EnvelopeError::LengthMismatch {
+                expected: payload.len(),
+                actual: bytes.len() - HEADER_LEN,
+            }
+            .into());
+        }
+        Self::from_envelope_payload(meta, &payload)
+    }
+
+    /// Decodes bitcode saved before envelopes, trying each [`v0`]
+    /// layout from newest to oldest.
+    #[cfg(feature = "bitcode")]
+    fn from_bare_bitcode(bytes: &[u8]) -> Option<Character> {
+        let old = bitcode::decode::<v0::Character>(bytes)
+            .or_else(|_| bitcode::decode::<v0::PreInventoryCharacter>(bytes).map(Into::into))
+            .ok()?;
+        Some(Character {
+            id: fnv1a_id(bytes),
+            ..old.into()
+        })
+    }
+
+    /// Encodes the character as bitcode without its `temp_effects`,
+    /// which no save keeps.
+    #[cfg(feature = "bitcode")]
+    pub(crate) fn encode_saved(&self) -> Vec<u8> {
+        if self.temp_effects.is_empty() {
+            bitcode::encode(self)
+        } else {
+            bitcode::encode(&Character {
+                temp_effects: Vec::new(),
+                ..self.clone()
+            })
+        }
+    }
+
+    /// Decodes a payload read by
+    /// [`read_envelope`](crate::serialization::envelope::read_envelope).
+    #[cfg(feature = "bitcode")]
+    pub(crate) fn from_envelope_payload(
+        meta: EnvelopeMeta,
+        payload: &[u8],
+    ) -> Result<Character, ArrataError> {
+        match meta.encoding {
+            // One arm per envelope version. `read_envelope` has
+            // already rejected versions newer than this build.
+            PayloadEncoding::Bitcode => match meta.version {
+                1 => {
+                    // Older builds saved temp effects in binary saves.
+                    let character: Character = bitcode::decode::<v1::Character>(payload)?.into();
+                    Ok(Character {
+                        temp_effects: Vec::new(),
+                        ..character
+                    })
+                }
+                version => Err(EnvelopeError::UnknownVersion(version).into()),
+            },
+            #[cfg(feature = "serde")]
+            PayloadEncoding::Json => Ok(serde_json::from_slice(payload)?),
+            #[cfg(not(feature = "serde"))]
+            PayloadEncoding::Json => Err(EnvelopeError::UnknownEncoding(meta.encoding.id()).into()),
+        }
+    }
+
+    /// Recovers a character from JSON saved before saves were
+    /// versioned, whose layout may differ slightly from today's.
+    ///
+    /// Plain JSON loading already fills in missing top-level fields
+    /// and splits single-string argos. On top of that, this:
+    /// - treats `null` fields as missing, so they take defaults,
+    /// - fills in quirk, resource, item, and stat fields that older
+    ///   tools left out, as empty, zero, `Basic`, or `Uncategorized`
+    ///   (stats default to `B1`, as [`Stat::new`]),
+    /// - and accepts qualities by initial or in any case, like `"b"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArrataError::QualityParse`] for a quality that is
+    /// no quality at all, like `"Legendary"`, rather than guessing,
+    /// and `ArrataError::Json` if the value still isn't a character
+    /// once migrated.
+    #[cfg(feature = "serde")]
+    pub fn import_legacy(mut value: serde_json::Value) -> Result<Character, ArrataError> {
+        if let Some(character) = value.as_object_mut() {
+            migrate_legacy(character)?;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// A lightweight overview for roster lists.
+    #[must_use]
+    pub fn summary(&self) -> CharacterSummary {
+        CharacterSummary {
+            name: self.name.clone(),
+            stock: self.stock.clone(),
+            power_level: self.power_level(),
+            quirk_count: self.quirks.len(),
+            item_count: self.inventory.len(),
+        }
+    }
+
+    /// Sorts the character's quirks for display.
+    ///
+    /// See [`Quirk::cmp_for_display`].
+    pub fn sort_quirks(&mut self) {
+        self.quirks.sort_by(Quirk::cmp_for_display);
+    }
+
+    /// Returns a sorted copy of the character's quirks,
+    /// leaving the character untouched.
+    ///
+    /// Exports should use this rather than [`Character::sort_quirks`].
+    #[must_use]
+    pub fn sorted_quirks(&self) -> Vec<Quirk> {
+        let mut quirks = self.quirks.clone();
+        quirks.sort_by(Quirk::cmp_for_display);
+        quirks
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for Character {
+    fn default() -> Self {
+        Character {
+            id: new_id(),
+            name: "Name".to_string(),
+            stock: "Stock".to_string(),
+            stats: vec![
+                Stat::new("Will".into()),
+                Stat::new("Perception".into()),
+                Stat::new("Conscious".into()),
+                Stat::new("Power".into()),
+                Stat::new("Speed".into()),
+                Stat::new("Forte".into()),
+            ],
+            skills: Vec::new(),
+            quirks: Vec::new(),
+            resources: Vec::new(),
+            inspiration: Inspiration::new(),
+            argos: Argos::new(),
+            inventory: Vec::new(),
+            macros: Vec::new(),
+            inspiration_log: Vec::new(),
+            temp_effects: Vec::new(),
+        }
+    }
+}
+
+/// A compact overview of a character, from [`Character::summary`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CharacterSummary {
+    pub name: String,
+    pub stock: String,
+    /// See [`Character::power_level`].
+    pub power_level: usize,
+    pub quirk_count: usize,
+    /// The number of entries in the inventory, not the
+    /// total quantity.
+    pub item_count: usize,
+}
+
+/// A `| name | value | checks |` row for [`Character::to_markdown`].
+fn push_stat_row(out: &mut String, stat: &Stat) {
+    out.push_str("| ");
+    out.push_str(&markdown::escape(&stat.name));
+    out.push_str(" | ");
+    out.push_str(&stat.to_string());
+    out.push_str(" | ");
+    match stat.checks {
+        Some(checks) => out.push_str(&checks.to_string()),
+        None => out.push('-'),
+    }
+    out.push_str(" |\n");
+}
+
+/// The number of checks one of the core stats needs before
+/// [`Character::record_use`] advances it.
+pub const CHECKS_TO_ADVANCE: usize = 5;
+
+/// The number of checks a skill needs before
+/// [`Character::record_use`] advances it. Skills are narrower
+/// than the core stats, so they grow faster.
+pub const SKILL_CHECKS_TO_ADVANCE: usize = 3;
+
+/// The highest quantity a stat reaches before [`Stat::advance`]
+/// moves it up a quality tier.
+pub const MAX_QUANTITY: usize = 6;
+
+/// The rule [`advancement_eligible`] and
+/// [`Character::record_check_if_eligible`] use to decide whether an
+/// obstacle challenged a stat. Hacks with a different rule can call
+/// [`advancement_eligible_with`] or
+/// [`Character::record_check_if_eligible_with`] instead.
+pub const ELIGIBILITY_RULE: ObstacleRule = ObstacleRule::HalfQuantityRoundedUp;
+
+/// Whether rolling a stat against an obstacle was hard enough to
+/// earn a check: the obstacle must be at least half the stat's
+/// quantity, rounded up. See [`ELIGIBILITY_RULE`].
+#[must_use]
+pub fn advancement_eligible(stat: &Stat, obstacle: &Obstacle) -> bool {
+    advancement_eligible_with(stat, obstacle, ELIGIBILITY_RULE)
+}
+
+/// Whether rolling a stat against an obstacle was hard enough to
+/// earn a check, with the obstacle compared against the one `rule`
+/// derives from the stat.
+#[must_use]
+pub fn advancement_eligible_with(stat: &Stat, obstacle: &Obstacle, rule: ObstacleRule) -> bool {
+    *obstacle >= Obstacle::from_stat(stat, rule)
+}
+
+/// A struct for Stats.
+///
+/// `checks` is optional as some stats don't
+/// require checks to function.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Stat {
+    pub name: String,
+    pub quality: Quality,
+    pub quantity: usize,
+    pub checks: Option<usize>,
+}
+
+impl Stat {
+    #[must_use]
+    pub fn new(name: String) -> Stat {
+        Stat {
+            name,
+            quality: Quality::Basic,
+            quantity: 1,
+            checks: Some(0),
+        }
+    }
+
+    /// Whether the stat is at least as good as the given
+    /// quality and quantity.
+    ///
+    /// A better quality always meets a worse one, regardless
+    /// of quantity.
+    #[must_use]
+    pub fn meets(&self, quality: Quality, quantity: usize) -> bool {
+        self.quality > quality || (self.quality == quality && self.quantity >= quantity)
+    }
+
+    /// Adds a check to the stat.
+    ///
+    /// Stats with `checks: None` can't earn checks, so this
+    /// does nothing and returns `false` for them.
+    pub fn add_check(&mut self) -> bool {
+        match &mut self.checks {
+            Some(checks) => {
+                *checks += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Advances the stat's quantity by one if it has at least
+    /// `threshold` checks, resetting its checks to zero.
+    ///
+    /// Returns whether the stat advanced. Stats with `checks: None`
+    /// never advance this way and are left untouched.
+    pub fn try_advance(&mut self, threshold: usize) -> bool {
+        match self.checks {
+            Some(checks) if checks >= threshold => {
+                self.quantity += 1;
+                self.checks = Some(0);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Advances the stat one step under the full advancement rule.
+    ///
+    /// Quantity goes up by one until [`MAX_QUANTITY`]. A stat already
+    /// at the cap instead moves up a quality tier and drops back to
+    /// a quantity of one, so B6 becomes A1. Superb is the top tier,
+    /// so a Superb stat at the cap stays put.
+    ///
+    /// Returns whether the stat changed. Checks are left untouched.
+    pub fn advance(&mut self) -> bool {
+        if self.quantity < MAX_QUANTITY {
+            self.quantity += 1;
+        } else if self.quality == Quality::Superb {
+            return false;
+        } else {
+            self.quality = self.quality.shifted(1);
+            self.quantity = 1;
+        }
+        true
+    }
+
+    /// The largest quantity [`Stat::to_compact`] can hold.
+    pub const MAX_COMPACT_QUANTITY: usize = 0x3FFF;
+
+    /// Packs the stat's quality and quantity into 16 bits, for
+    /// bandwidth-sensitive sync.
+    ///
+    /// The top 2 bits are the quality, `0` for Basic, `1` for Adept,
+    /// and `2` for Superb. The low 14 bits are the quantity, clamped
+    /// to [`Stat::MAX_COMPACT_QUANTITY`]. The name and checks aren't
+    /// included.
+    #[must_use]
+    pub fn to_compact(&self) -> u16 {
+        let quality: u16 = match self.quality {
+            Quality::Basic => 0,
+            Quality::Adept => 1,
+            Quality::Superb => 2,
+        };
+        let quantity =
+            u16::try_from(self.quantity.min(Self::MAX_COMPACT_QUANTITY)).unwrap_or(u16::MAX >> 2);
+        quality << 14 | quantity
+    }
+
+    /// Unpacks a stat from [`Stat::to_compact`], with an empty name
+    /// and zero checks.
+    ///
+    /// The unused quality value `3` reads as Basic.
+    #[must_use]
+    pub fn from_compact(compact: u16) -> Stat {
+        Stat {
+            name: String::new(),
+            quality: match compact >> 14 {
+                1 => Quality::Adept,
+                2 => Quality::Superb,
+                _ => Quality::Basic,
+            },
+            quantity: usize::from(compact & 0x3FFF),
+            checks: Some(0),
+        }
+    }
+
+    /// Moves the stat up a quality tier, keeping its quantity.
+    ///
+    /// With `reset_checks`, accumulated checks go back to zero, as
+    /// many tables do on a quality change. Stats with `checks: None`
+    /// keep `None` either way.
+    ///
+    /// Returns whether the quality changed, which it doesn't for
+    /// a stat that's already Superb.
+    pub fn upgrade_quality(&mut self, reset_checks: bool) -> bool {
+        self.change_quality(1, reset_checks)
+    }
+
+    /// Moves the stat down a quality tier, keeping its quantity.
+    ///
+    /// Checks are handled as in [`Stat::upgrade_quality`]. Returns
+    /// whether the quality changed, which it doesn't for a stat
+    /// that's already Basic.
+    pub fn downgrade_quality(&mut self, reset_checks: bool) -> bool {
+        self.change_quality(-1, reset_checks)
+    }
+
+    fn change_quality(&mut self, steps: i8, reset_checks: bool) -> bool {
+        let quality = self.quality.shifted(steps);
+        if quality == self.quality {
+            return false;
+        }
+        self.quality = quality;
+        if reset_checks {
+            if let Some(checks) = &mut self.checks {
+                *checks = 0;
+            }
+        }
+        true
+    }
+}
+
+impl From<String> for Stat {
+    /// Given in the form `{Quality}{Quantity}`.
+    /// No `name` or `checks` field are accepted.
+    ///
+    /// This never fails: an unknown quality is Basic and a missing
+    /// or malformed quantity is 1. Use [`stats_from_tsv`] to reject
+    /// malformed stats instead.
+    fn from(value: String) -> Self {
+        let mut chars = value.chars();
+        if let Some(first_char) = chars.next() {
+            let quality = match first_char {
+                'A' | 'a' => Quality::Adept,
+                'S' | 's' => Quality::Superb,
+                _ => Quality::Basic,
+            };
+            let quantity = chars.as_str().parse::<usize>().unwrap_or(1);
+            Stat {
+                name: String::new(),
+                quality,
+                quantity,
+                checks: Some(0),
+            }
+        } else {
+            Stat::new(String::new())
+        }
+    }
+}
+
+impl core::fmt::Display for Stat {
+    /// Honors width, fill, and alignment, so `{:>4}` lines up columns.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let quality = match self.quality {
+            Quality::Basic => 'B',
+            Quality::Adept => 'A',
+            Quality::Superb => 'S',
+        };
+        f.pad(&format!("{quality}{}", self.quantity))
+    }
+}
+
+/// Limits on how many quirks a character may take.
+///
+/// `per_category` applies to Ethos, Pathos, and Logos separately.
+/// Uncategorized quirks are exempt from it and only count toward
+/// `total`. Both default to `None`, meaning unlimited.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuirkLimits {
+    pub per_category: Option<usize>,
+    pub total: Option<usize>,
+}
+
+impl QuirkLimits {
+    fn check(&self, quirks: &[Quirk]) -> Vec<ValidationIssue> {
+        self.check_categories(quirks, |_| true)
+    }
+
+    /// Like [`QuirkLimits::check`], only checking `per_category` for
+    /// the categories `include` accepts.
+    fn check_categories(
+        &self,
+        quirks: &[Quirk],
+        include: impl Fn(&QuirkCategory) -> bool,
+    ) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        if let Some(limit) = self.per_category {
+            for category in QuirkCategory::iter() {
+                if category == QuirkCategory::Uncategorized || !include(&category) {
+                    continue;
+                }
+                let count = quirks
+                    .iter()
+                    .filter(|quirk| quirk.category == category)
+                    .count();
+                if count > limit {
+                    issues.push(ValidationIssue::TooManyInCategory {
+                        category,
+                        limit,
+                        excess: count - limit,
+                    });
+                }
+            }
+        }
+
+        if let Some(limit) = self.total {
+            if quirks.len() > limit {
+                issues.push(ValidationIssue::TooManyQuirks {
+                    limit,
+                    excess: quirks.len() - limit,
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/// An error produced by operations on a [`Character`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CharacterError {
+    /// The character has no quirk with this name.
+    QuirkNotFound(String),
+    /// The character has no stat or skill with this name.
+    StatNotFound(String),
+}
+
+impl core::fmt::Display for CharacterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CharacterError::QuirkNotFound(name) => write!(f, "no quirk named `{name}`"),
+            CharacterError::StatNotFound(name) => write!(f, "no stat or skill named `{name}`"),
+        }
+    }
+}
+
+impl core::error::Error for CharacterError {}
+
+/// A problem found by [`Character::validate`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum ValidationIssue {
+    /// A quirk the character has whose prerequisites aren't met.
+    UnmetPrerequisite {
+        quirk: String,
+        unmet: UnmetPrerequisite,
+    },
+    /// More quirks in a category than [`QuirkLimits::per_category`] allows.
+    TooManyInCategory {
+        category: QuirkCategory,
+        limit: usize,
+        excess: usize,
+    },
+    /// More quirks than [`QuirkLimits::total`] allows.
+    TooManyQuirks { limit: usize, excess: usize },
+    /// The character's name is empty or only whitespace.
+    EmptyName,
+    /// More than one stat or skill with this name, ignoring case.
+    DuplicateStat(String),
+}
+
+impl core::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ValidationIssue::UnmetPrerequisite { quirk, unmet } => write!(f, "{quirk} {unmet}"),
+            ValidationIssue::TooManyInCategory {
+                category,
+                limit,
+                excess,
+            } => write!(
+                f,
+                "{excess} more {category} quirk(s) than the limit of {limit}"
+            ),
+            ValidationIssue::TooManyQuirks { limit, excess } => {
+                write!(f, "{excess} more quirk(s) than the limit of {limit}")
+            }
+            ValidationIssue::EmptyName => write!(f, "the character has no name"),
+            ValidationIssue::DuplicateStat(name) => {
+                write!(f, "more than one stat or skill named `{name}`")
+            }
+        }
+    }
+}
+
+/// The error from [`Character::from_parts`], listing every
+/// problem found.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ValidationError {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl core::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid character")?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            write!(f, "{} {issue}", if i == 0 { ":" } else { ";" })?;
+        }
+        Ok(())
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// Parses named stats from tab-separated lines, such as those
+/// copied out of a spreadsheet.
+///
+/// Each line is a name and a value, e.g. `Power\tA4`. Blank lines
+/// are skipped.
+///
+/// # Errors
+///
+/// Returns a [`StatParseError`] naming the first offending line.
+pub fn stats_from_tsv(input: &str) -> Result<Vec<Stat>, StatParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let error = |kind| StatParseError {
+                line: index + 1,
+                kind,
+            };
+
+            let (name, value) = line
+                .split_once('\t')
+                .ok_or_else(|| error(StatParseErrorKind::MissingValue))?;
+            let (name, value) = (name.trim(), value.trim());
+
+            if name.is_empty() {
+                return Err(error(StatParseErrorKind::EmptyName));
+            }
+            if !is_stat_value(value) {
+                return Err(error(StatParseErrorKind::InvalidValue(value.to_string())));
+            }
+
+            let mut stat = Stat::from(value.to_string());
+            stat.name = name.to_string();
+            Ok(stat)
+        })
+        .collect()
+}
+
+/// Whether a value is a well-formed `{Quality}{Quantity}`.
+fn is_stat_value(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some('B' | 'b' | 'A' | 'a' | 'S' | 's'))
+        && chars.as_str().parse::<usize>().is_ok()
+}
+
+/// An error produced while parsing stats from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatParseError {
+    /// The 1-indexed line the error occurred on.
+    pub line: usize,
+    pub kind: StatParseErrorKind,
+}
+
+/// The kind of [`StatParseError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatParseErrorKind {
+    /// The line had no tab-separated value.
+    MissingValue,
+    /// The line had no name.
+    EmptyName,
+    /// The value was not in the form `{Quality}{Quantity}`.
+    InvalidValue(String),
+}
+
+impl core::fmt::Display for StatParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            StatParseErrorKind::MissingValue => write!(f, "missing tab-separated value"),
+            StatParseErrorKind::EmptyName => write!(f, "missing stat name"),
+            StatParseErrorKind::InvalidValue(value) => {
+                write!(f, "`{value}` is not a stat like `B4`")
+            }
+        }
+    }
+}
+
+impl core::error::Error for StatParseError {}
+
+/// A character's argos: their discrete beliefs and goals.
+///
+/// Older saves stored the argos as a single string. Those still
+/// load, with each non-empty line becoming its own entry.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Argos(pub Vec<String>);
+
+impl Argos {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, entry: String) {
+        self.0.push(entry);
+    }
+
+    /// Removes the entry at `index`, if any.
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        (index < self.0.len()).then(|| self.0.remove(index))
+    }
+
+    /// Replaces the entry at `index`, returning the old entry if any.
+    pub fn edit(&mut self, index: usize, entry: String) -> Option<String> {
+        self.0
+            .get_mut(index)
+            .map(|old| core::mem::replace(old, entry))
+    }
+}
+
+impl From<&str> for Argos {
+    /// Splits legacy single-string argos into one entry per line.
+    fn from(value: &str) -> Self {
+        Self(
+            value
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+}
+
+impl core::ops::Deref for Argos {
+    type Target = [String];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+#[cfg_attr(feature = "serde", serde(untagged))]
+enum ArgosRepr {
+    Legacy(String),
+    Entries(Vec<String>),
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Argos {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ArgosRepr::deserialize(deserializer)? {
+            ArgosRepr::Legacy(argos) => Argos::from(argos.as_str()),
+            ArgosRepr::Entries(entries) => Argos(entries),
+        })
+    }
+}
+
+/// A block of stats, such as one built by an NPC generator.
+///
+/// Derefs to a slice of [`Stat`]s and can be `collect()`ed into.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct StatBlock(pub Vec<Stat>);
+
+impl StatBlock {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Finds a stat by name, ignoring case.
+    #[must_use]
+    pub fn stat(&self, name: &str) -> Option<&Stat> {
+        self.0
+            .iter()
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Finds a stat by name mutably, ignoring case.
+    pub fn stat_mut(&mut self, name: &str) -> Option<&mut Stat> {
+        self.0
+            .iter_mut()
+            .find(|stat| stat.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn push(&mut self, stat: Stat) {
+        self.0.push(stat);
+    }
+}
+
+impl FromIterator<Stat> for StatBlock {
+    fn from_iter<T: IntoIterator<Item = Stat>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for StatBlock {
+    type Item = Stat;
+    type IntoIter = alloc::vec::IntoIter<Stat>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl core::ops::Deref for StatBlock {
+    type Target = [Stat];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for StatBlock {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<Vec<Stat>> for StatBlock {
+    fn from(value: Vec<Stat>) -> Self {
+        Self(value)
+    }
+}
+
+impl From<StatBlock> for Vec<Stat> {
+    fn from(value: StatBlock) -> Self {
+        value.0
+    }
+}
+
+/// An abstraction for resources.
+///
+/// Effectively a stat with a boolean defining finite/infinite status.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Resource {
+    /// The stat associated with the resource.
+    pub stat: Stat,
+    /// Whether the resource is finite.
+    pub finite: bool,
+}
+
+impl Resource {
+    #[must_use]
+    pub fn new(stat: Stat, finite: bool) -> Self {
+        Self { stat, finite }
+    }
+}
+
+impl Default for Resource {
+    fn default() -> Self {
+        Self::new(Stat::new("New Resource!".into()), false)
+    }
+}
+
+/// A struct for Quality. Determines the
+/// lower bound for rolls.
+///
+/// Qualities are ordered from worst to best, so
+/// `Quality::Basic < Quality::Superb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Quality {
+    Basic = 4,
+    Adept = 3,
+    Superb = 2,
+}
+
+impl PartialOrd for Quality {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Quality {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // A lower threshold is a better quality.
+        other.threshold().cmp(&self.threshold())
+    }
+}
+
+impl Quality {
+    /// The lowest face that counts as a success.
+    #[must_use]
+    pub fn threshold(self) -> u8 {
+        self as u8
+    }
+
+    /// Shifts the quality by `steps` tiers, positive being better.
+    ///
+    /// Clamps at Basic and Superb.
+    #[must_use]
+    pub fn shifted(self, steps: i8) -> Quality {
+        const TIERS: [Quality; 3] = [Quality::Basic, Quality::Adept, Quality::Superb];
+        let current = TIERS.iter().position(|tier| *tier == self).unwrap_or(0);
+        let shifted = current
+            .saturating_add_signed(isize::from(steps))
+            .min(TIERS.len() - 1);
+        TIERS[shifted]
+    }
+}
+
+impl core::fmt::Display for Quality {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad(match self {
+            Quality::Basic => "Basic",
+            Quality::Adept => "Adept",
+            Quality::Superb => "Superb",
+        })
+    }
+}
+
+impl core::str::FromStr for Quality {
+    type Err = QualityParseError;
+
+    /// Parses a quality's name or initial, like `Adept` or `A`,
+    /// ignoring case and surrounding whitespace.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        [Quality::Basic, Quality::Adept, Quality::Superb]
+            .into_iter()
+            .find(|quality| {
+                let name = quality.to_string();
+                value.eq_ignore_ascii_case(&name) || value.eq_ignore_ascii_case(&name[..1])
+            })
+            .ok_or_else(|| QualityParseError(value.to_string()))
+    }
+}
+
+/// An error parsing a [`Quality`], holding the rejected text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualityParseError(pub String);
+
+impl core::fmt::Display for QualityParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "`{}` is not Basic, Adept, or Superb", self.0)
+    }
+}
+
+impl core::error::Error for QualityParseError {}
+
+/// An arbitrary success threshold for homebrew tiers.
+///
+/// Any face greater than or equal to the inner value
+/// is a success. See [`crate::roll_stat_rules`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CustomQuality(pub u8);
+
+impl From<Quality> for CustomQuality {
+    fn from(value: Quality) -> Self {
+        CustomQuality(value.threshold())
+    }
+}
+
+/// A temporary shift to a stat from a spell or condition.
+///
+/// `quality_shift` is in tiers, positive being better. `expires_in`
+/// is the number of [`Character::tick_temps`] calls until the effect
+/// ends; an effect at zero is inactive.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TempEffect {
+    pub stat: String,
+    pub quality_shift: i8,
+    pub quantity_delta: isize,
+    pub expires_in: usize,
+}
+
+impl TempEffect {
+    #[must_use]
+    pub fn new(stat: String, quality_shift: i8, quantity_delta: isize, expires_in: usize) -> Self {
+        Self {
+            stat,
+            quality_shift,
+            quantity_delta,
+            expires_in,
+        }
+    }
+
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.expires_in > 0
+    }
+}
+
+/// A struct for items.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Item {
+    pub name: String,
+    pub quantity: usize,
+    pub description: String,
+    /// The most that fit in one stack, such as 20 arrows,
+    /// or `None` for no limit.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub max_stack: Option<usize>,
+}
+
+impl Item {
+    #[must_use]
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            quantity: 0,
+            description: String::new(),
+            max_stack: None,
+        }
+    }
+
+    /// Adds to the stack up to its `max_stack`.
+    ///
+    /// Returns how many didn't fit.
+    pub fn add(&mut self, amount: usize) -> usize {
+        let room = self
+            .max_stack
+            .map_or(usize::MAX, |max| max.saturating_sub(self.quantity))
+            .min(usize::MAX - self.quantity);
+        let added = amount.min(room);
+        self.quantity += added;
+        amount - added
+    }
+}
+
+impl Default for Item {
+    fn default() -> Self {
+        Self::new("New Item!".into())
+    }
+}
+
+/// A saved roll, such as a signature attack.
+///
+/// `stat` is the name of a stat or skill on the character.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RollMacro {
+    pub name: String,
+    pub stat: String,
+    pub advantage: usize,
+    pub disadvantage: usize,
+}
+
+impl RollMacro {
+    #[must_use]
+    pub fn new(name: String, stat: String) -> Self {
+        Self {
+            name,
+            stat,
+            advantage: 0,
+            disadvantage: 0,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serde")]
+    use crate::test_support::assert_json_roundtrip;
+    #[cfg(feature = "bitcode")]
+    use crate::test_support::{assert_bitcode_roundtrip, assert_envelope_roundtrip};
+
+    fn character() -> Character {
+        let mut character = Character::new("Ash".into());
+        character.stat_mut("Will").unwrap().quantity = 4;
+        character
+    }
+
+    fn quirk(boons: &[&str], flaws: &[&str]) -> Quirk {
+        Quirk {
+            boons: boons.iter().map(ToString::to_string).collect(),
+            flaws: flaws.iter().map(ToString::to_string).collect(),
+            ..Quirk::new("Test".into())
+        }
+    }
+
+    #[test]
+    fn roll_modifiers_read_quirks_and_carried_items() {
+        let mut character = character();
+        character.quirks.push(quirk(
+            &["Advantage on Will."],
+            &[
+                "Disadvantage on Will vs fear.",
+                "2 disadvantage on Will while carrying a torch.",
+            ],
+        ));
+        assert_eq!(character.roll_modifiers("will"), (1, 0));
+
+        let mut torch = Item::new("Torch".into());
+        torch.quantity = 1;
+        character.inventory.push(torch);
+        assert_eq!(character.roll_modifiers("Will"), (1, 2));
+        assert_eq!(character.roll_modifiers("Power"), (0, 0));
+    }
+
+    #[test]
+    fn roll_in_scene_stacks_scene_on_quirk_modifiers() {
+        let scene = SceneModifier::new("Dark".into(), 0, 2);
+        let mut character = character();
+        // Disadvantage 2 removes one of Will's four dice.
+        assert_eq!(
+            character
+                .roll_in_scene("Will", &scene)
+                .unwrap()
+                .results
+                .len(),
+            3
+        );
+
+        // Stacked to disadvantage 4, three of the four are removed.
+        character
+            .quirks
+            .push(quirk(&[], &["2 disadvantage on Will"]));
+        for _ in 0..20 {
+            let result = character.roll_in_scene("Will", &scene).unwrap();
+            assert_eq!(result.results.len(), 1);
+        }
+        assert!(character.roll_in_scene("Nothing", &scene).is_none());
+    }
+
+    #[test]
+    fn roll_in_scene_applies_passive_modifiers_and_temps() {
+        let scene = SceneModifier::default();
+        let mut character = character();
+        character.quirks.push(quirk(&["+2 quantity to Will"], &[]));
+        character
+            .temp_effects
+            .push(TempEffect::new("Will".into(), 0, -1, 1));
+        assert_eq!(
+            character
+                .roll_in_scene("Will", &scene)
+                .unwrap()
+                .results
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn run_macro_applies_quirk_modifiers() {
+        let mut character = character();
+        let mut roll_macro = RollMacro::new("Stare".into(), "will".into());
+        roll_macro.disadvantage = 2;
+        character.macros.push(roll_macro);
+        assert_eq!(character.run_macro("STARE").unwrap().results.len(), 3);
+
+        character
+            .quirks
+            .push(quirk(&["+1 quantity to Will"], &["Disadvantage on Will"]));
+        // Five dice at disadvantage 3 leaves three.
+        assert_eq!(character.run_macro("Stare").unwrap().results.len(), 3);
+        character.quirks[0].flaws.clear();
+        assert_eq!(character.run_macro("Stare").unwrap().results.len(), 4);
+        assert!(character.run_macro("Missing").is_none());
+    }
+
+    #[test]
+    fn sorted_quirks_pins_display_order() {
+        let mut character = character();
+        for (name, category, description) in [
+            ("bold", QuirkCategory::Logos, ""),
+            ("Zeal", QuirkCategory::Uncategorized, ""),
+            ("Alert", QuirkCategory::Ethos, "first"),
+            ("calm", QuirkCategory::Pathos, ""),
+            ("alert", QuirkCategory::Ethos, "second"),
+        ] {
+            character.quirks.push(Quirk {
+                category,
+                description: description.into(),
+                ..Quirk::new(name.into())
+            });
+        }
+        let original = character.quirks.clone();
+
+        let sorted = character.sorted_quirks();
+        let order: Vec<(&str, &str)> = sorted
+            .iter()
+            .map(|quirk| (quirk.name.as_str(), quirk.description.as_str()))
+            .collect();
+        // Duplicate names keep their insertion order.
+        assert_eq!(
+            order,
+            [
+                ("Alert", "first"),
+                ("alert", "second"),
+                ("calm", ""),
+                ("bold", ""),
+                ("Zeal", ""),
+            ]
+        );
+        assert_eq!(character.quirks, original);
+
+        character.sort_quirks();
+        assert_eq!(character.quirks, sorted);
+    }
+
+    #[test]
+    fn add_quirk_checked_only_checks_the_new_quirks_category() {
+        let limits = QuirkLimits {
+            per_category: Some(1),
+            total: None,
+        };
+        let mut character = character();
+        for name in ["Brave", "Loyal"] {
+            character.quirks.push(Quirk {
+                category: QuirkCategory::Ethos,
+                ..Quirk::new(name.into())
+            });
+        }
+        let logos = Quirk {
+            category: QuirkCategory::Logos,
+            ..Quirk::new("Curious".into())
+        };
+        assert_eq!(character.add_quirk_checked(logos.clone(), &limits), Ok(()));
+
+        let issues = character.add_quirk_checked(logos, &limits).unwrap_err();
+        assert_eq!(
+            issues,
+            [ValidationIssue::TooManyInCategory {
+                category: QuirkCategory::Logos,
+                limit: 1,
+                excess: 1,
+            }]
+        );
+        assert_eq!(character.quirks.len(), 3);
+
+        let capped = QuirkLimits {
+            per_category: None,
+            total: Some(3),
+        };
+        let issues = character
+            .add_quirk_checked(Quirk::new("Odd".into()), &capped)
+            .unwrap_err();
+        assert_eq!(
+            issues,
+            [ValidationIssue::TooManyQuirks {
+                limit: 3,
+                excess: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn repeated_quirk_names_get_unique_anchors() {
+        let mut character = character();
+        character.quirks.push(quirk(&[], &[]));
+        character.quirks.push(quirk(&[], &[]));
+        character.quirks.push(Quirk {
+            category: QuirkCategory::Logos,
+            ..quirk(&[], &[])
+        });
+        let markdown = character.quirks_to_markdown();
+        let anchors: Vec<&str> = markdown
+            .lines()
+            .filter_map(|line| line.strip_prefix("<a id=\""))
+            .filter_map(|line| line.strip_suffix("\"></a>"))
+            .collect();
+        assert_eq!(anchors, ["test", "test-2", "test-3"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn saves_without_an_id_load_with_the_same_id() {
+        let legacy = r#"{"name": "Ash", "stock": "Human"}"#;
+        let first: Character = serde_json::from_str(legacy).unwrap();
+        let second: Character = serde_json::from_str(legacy).unwrap();
+        assert_ne!(first.id(), 0);
+        assert_eq!(first.id(), second.id());
+
+        let other: Character = serde_json::from_str(r#"{"name": "Bo", "stock": "Human"}"#).unwrap();
+        assert_ne!(first.id(), other.id());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn saved_ids_are_kept() {
+        assert_json_roundtrip(&character());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn prerewrite_quirk_types_load_and_save_as_categories() {
+        let character: Character = serde_json::from_str(include_str!(
+            "../tests/fixtures/legacy/prerewrite_character.json"
+        ))
+        .unwrap();
+        let categories: Vec<QuirkCategory> = character
+            .quirks
+            .iter()
+            .map(|quirk| quirk.category.clone())
+            .collect();
+        assert_eq!(
+            categories,
+            [
+                QuirkCategory::Ethos,
+                QuirkCategory::Pathos,
+                QuirkCategory::Logos,
+                QuirkCategory::Uncategorized,
+            ]
+        );
+
+        let saved = serde_json::to_value(&character).unwrap();
+        let saved: Vec<(bool, &str)> = saved["quirks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|quirk| {
+                (
+                    quirk.get("type").is_some(),
+                    quirk["category"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            saved,
+            [
+                (false, "Ethos"),
+                (false, "Pathos"),
+                (false, "Logos"),
+                (false, "Uncategorized"),
+            ]
+        );
+    }
+
+    #[test]
+    fn stat_display_honors_width_and_alignment() {
+        let stat = Stat {
+            quality: Quality::Adept,
+            quantity: 4,
+            ..Stat::new("Will".into())
+        };
+        assert_eq!(format!("{stat}"), "A4");
+        assert_eq!(format!("{stat:>6}"), "    A4");
+        assert_eq!(format!("{stat:<6}|"), "A4    |");
+        assert_eq!(format!("{stat:*^6}"), "**A4**");
+        assert_eq!(format!("{stat:1}"), "A4");
+    }
+
+    #[test]
+    fn stats_without_checks_never_earn_them() {
+        let mut stat = Stat {
+            checks: None,
+            ..Stat::new("Wealth".into())
+        };
+        let before = stat.clone();
+        assert!(!stat.add_check());
+        assert!(!stat.try_advance(0));
+        assert_eq!(stat, before);
+
+        let mut character = character();
+        character.stat_mut("Will").unwrap().checks = None;
+        let before = character.stat("Will").unwrap().clone();
+        for _ in 0..=CHECKS_TO_ADVANCE {
+            assert!(!character.record_use("Will").unwrap());
+        }
+        assert!(!character
+            .record_check_if_eligible("will", &Obstacle::new(10))
+            .unwrap());
+        assert_eq!(character.stat("Will").unwrap(), &before);
+    }
+
+    #[test]
+    fn stats_with_checks_earn_them() {
+        let mut stat = Stat {
+            checks: Some(0),
+            ..Stat::new("Will".into())
+        };
+        assert!(stat.add_check());
+        assert!(!stat.try_advance(2));
+        assert!(stat.add_check());
+        assert!(stat.try_advance(2));
+        assert_eq!(stat.checks, Some(0));
+        assert_eq!(stat.quantity, 2);
+    }
+
+    #[test]
+    fn apply_quirk_update_replaces_by_name_and_keeps_session_state() {
+        let mut character = character();
+        character.quirks.push(Quirk {
+            uses_this_session: 2,
+            session_notes: vec!["Held the door.".into()],
+            ..quirk(&["Advantage on Will."], &[])
+        });
+        let updated = Quirk {
+            name: "TEST".into(),
+            ..quirk(&["Advantage on Will.", "+1 Forte."], &[])
+        };
+
+        let diff = character.apply_quirk_update(updated).unwrap();
+        assert_eq!(diff.added_boons, ["+1 Forte."]);
+        assert!(diff.removed_boons.is_empty());
+
+        let quirk = &character.quirks[0];
+        assert_eq!(quirk.name, "TEST");
+        assert_eq!(quirk.boons, ["Advantage on Will.", "+1 Forte."]);
+        assert_eq!(quirk.uses_this_session, 2);
+        assert_eq!(quirk.session_notes, ["Held the door."]);
+    }
+
+    #[test]
+    fn apply_quirk_update_rejects_unknown_quirks() {
+        let mut character = character();
+        let before = character.clone();
+        assert_eq!(
+            character.apply_quirk_update(Quirk::new("Missing".into())),
+            Err(CharacterError::QuirkNotFound("Missing".into()))
+        );
+        assert_eq!(character, before);
+    }
+
+    #[test]
+    fn record_check_if_eligible_follows_the_rule() {
+        // Will is B4: `Ob2` by half its quantity, `Ob3` under a fixed rule.
+        let fixed = ObstacleRule::FixedPerQuality {
+            basic: 3,
+            adept: 3,
+            superb: 3,
+        };
+        let mut character = character();
+        character.stat_mut("Will").unwrap().checks = Some(0);
+
+        assert!(!character
+            .record_check_if_eligible("Will", &Obstacle::new(1))
+            .unwrap());
+        assert!(character
+            .record_check_if_eligible("Will", &Obstacle::new(2))
+            .unwrap());
+        assert!(!character
+            .record_check_if_eligible_with("Will", &Obstacle::new(2), fixed)
+            .unwrap());
+        assert!(character
+            .record_check_if_eligible_with("Will", &Obstacle::new(3), fixed)
+            .unwrap());
+        assert_eq!(character.stat("Will").unwrap().checks, Some(2));
+
+        assert_eq!(
+            character.record_check_if_eligible_with("Luck", &Obstacle::new(3), fixed),
+            Err(CharacterError::StatNotFound("Luck".into()))
+        );
+    }
+
+    #[test]
+    fn quality_changes_reset_checks_only_when_asked() {
+        let mut stat = Stat {
+            checks: Some(3),
+            ..Stat::new("Will".into())
+        };
+        assert!(stat.upgrade_quality(false));
+        assert_eq!((stat.quality, stat.checks), (Quality::Adept, Some(3)));
+        assert!(stat.upgrade_quality(true));
+        assert_eq!((stat.quality, stat.checks), (Quality::Superb, Some(0)));
+
+        stat.checks = Some(2);
+        assert!(!stat.upgrade_quality(true));
+        assert_eq!((stat.quality, stat.checks), (Quality::Superb, Some(2)));
+        assert!(stat.downgrade_quality(true));
+        assert_eq!((stat.quality, stat.checks), (Quality::Adept, Some(0)));
+    }
+
+    #[test]
+    fn quality_changes_never_give_checks_to_stats_without_them() {
+        let mut stat = Stat {
+            checks: None,
+            ..Stat::new("Wealth".into())
+        };
+        for reset_checks in [false, true] {
+            assert!(stat.upgrade_quality(reset_checks));
+            assert_eq!(stat.checks, None);
+            assert!(stat.downgrade_quality(reset_checks));
+            assert_eq!(stat.checks, None);
+        }
+        assert!(!stat.downgrade_quality(true));
+        assert_eq!((stat.quality, stat.checks), (Quality::Basic, None));
+    }
+
+    #[test]
+    fn qualities_round_trip_through_their_names() {
+        for quality in [Quality::Basic, Quality::Adept, Quality::Superb] {
+            let name = quality.to_string();
+            assert_eq!(name.parse(), Ok(quality));
+            assert_eq!(name.to_lowercase().parse(), Ok(quality));
+            assert_eq!(name.to_uppercase().parse(), Ok(quality));
+            assert_eq!(name[..1].parse(), Ok(quality));
+            assert_eq!(name[..1].to_lowercase().parse(), Ok(quality));
+        }
+        assert_eq!(" adept ".parse(), Ok(Quality::Adept));
+    }
+
+    #[test]
+    fn unknown_qualities_are_rejected() {
+        for text in ["", "X", "Bas", "Basics", "Superior", "AB", "🎲"] {
+            assert_eq!(
+                text.parse::<Quality>(),
+                Err(QualityParseError(text.to_string())),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn compact_stats_round_trip_within_range() {
+        for quality in [Quality::Basic, Quality::Adept, Quality::Superb] {
+            for quantity in
+                (0..=64).chain([Stat::MAX_COMPACT_QUANTITY - 1, Stat::MAX_COMPACT_QUANTITY])
+            {
+                let stat = Stat {
+                    name: String::new(),
+                    quality,
+                    quantity,
+                    checks: Some(0),
+                };
+                assert_eq!(Stat::from_compact(stat.to_compact()), stat);
+            }
+        }
+    }
+
+    #[test]
+    fn compact_stats_pin_the_bit_layout() {
+        let stat = |quality, quantity| Stat {
+            quality,
+            quantity,
+            ..Stat::new("Will".into())
+        };
+        assert_eq!(stat(Quality::Basic, 4).to_compact(), 0x0004);
+        assert_eq!(stat(Quality::Adept, 4).to_compact(), 0x4004);
+        assert_eq!(stat(Quality::Superb, 0x3FFF).to_compact(), 0xBFFF);
+        assert_eq!(stat(Quality::Adept, usize::MAX).to_compact(), 0x7FFF);
+    }
+
+    #[test]
+    fn compact_stats_drop_names_and_checks() {
+        let stat = Stat {
+            checks: Some(5),
+            quality: Quality::Superb,
+            quantity: 3,
+            ..Stat::new("Will".into())
+        };
+        let back = Stat::from_compact(stat.to_compact());
+        assert_eq!(back.name, "");
+        assert_eq!(back.checks, Some(0));
+        assert_eq!((back.quality, back.quantity), (Quality::Superb, 3));
+        assert_eq!(Stat::from_compact(0xC002).quality, Quality::Basic);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn unknown_fields_from_newer_clients_are_ignored() {
+        let mut json = serde_json::to_value(character()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.insert(
+            "future_field".into(),
+            serde_json::json!({"nested": [1, 2, 3]}),
+        );
+        object["quirks"] = serde_json::json!([{
+            "name": "Stubborn",
+            "category": "Ethos",
+            "description": "",
+            "boons": [],
+            "flaws": [],
+            "future_quirk_field": true
+        }]);
+
+        let loaded: Character = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.name, "Ash");
+        assert_eq!(loaded.quirks[0].name, "Stubborn");
+
+        let saved = serde_json::to_value(&loaded).unwrap();
+        assert!(saved.get("future_field").is_none());
+        assert_json_roundtrip(&loaded);
+    }
+
+    /// The character in `tests/fixtures/saves/v0_character.bin`, saved
+    /// by 0.3.2. The pre-inventory fixture is the same, minus the rope.
+    #[cfg(feature = "bitcode")]
+    fn saved_by_0_3_2() -> Character {
+        let mut character = Character::new("Ash".into());
+        character.stock = "Human".into();
+        character.stats[0].quality = Quality::Adept;
+        character.stats[0].quantity = 4;
+        character.stats[1].checks = Some(2);
+        character.skills.push(Stat {
+            quality: Quality::Superb,
+            checks: None,
+            ..Stat::new("Climbing".into())
+        });
+        character
+            .resources
+            .push(Resource::new(Stat::new("Wealth".into()), true));
+        character.quirks.push(Quirk {
+            category: QuirkCategory::Pathos,
+            description: "Won't back down.".into(),
+            ..quirk(&["Resolve"], &["Pride"])
+        });
+        character.quirks[0].name = "Stubborn".into();
+        character.inspiration = Inspiration {
+            ethos: 1,
+            pathos: 2,
+            logos: 3,
+        };
+        character.argos = Argos::from("Find the sea");
+        character.inventory.push(Item {
+            quantity: 2,
+            description: "Fifty feet.".into(),
+            ..Item::new("Rope".into())
+        });
+        character
+    }
+
+    /// The character in `tests/fixtures/saves/v1_character.bin`, with
+    /// every field of the version 1 layout filled in.
+    #[cfg(feature = "bitcode")]
+    fn saved_in_v1() -> Character {
+        let mut character = saved_by_0_3_2();
+        character.id = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+        let quirk = &mut character.quirks[0];
+        quirk.prerequisites = vec![
+            Prerequisite::MinStat("Will".into(), Quality::Adept, 2),
+            Prerequisite::Stock("Human".into()),
+            Prerequisite::HasQuirk("Honest".into()),
+        ];
+        quirk.tags.push("social".into());
+        quirk.uses_this_session = 1;
+        quirk.session_notes.push("Refused the duke.".into());
+        character.inventory[0].max_stack = Some(5);
+        character.macros.push(RollMacro {
+            advantage: 1,
+            disadvantage: 2,
+            ..RollMacro::new("Climb".into(), "Climbing".into())
+        });
+        character.inspiration_log.push(InspirationEvent {
+            category: QuirkCategory::Logos,
+            delta: -1,
+            reason: "Spent on a climb.".into(),
+            session: "3".into(),
+        });
+        character
+            .temp_effects
+            .push(TempEffect::new("Speed".into(), -1, 2, 3));
+        character
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn v1_fixture_loads() {
+        let bytes = include_bytes!("../tests/fixtures/saves/v1_character.bin");
+        // Saved by a build that still kept temp effects in envelopes.
+        assert_eq!(
+            Character::from_envelope_bytes(bytes).unwrap(),
+            Character {
+                temp_effects: Vec::new(),
+                ..saved_in_v1()
+            }
+        );
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn characters_round_trip_through_bitcode_and_envelopes() {
+        assert_bitcode_roundtrip(&saved_in_v1());
+        assert_envelope_roundtrip(&Character {
+            temp_effects: Vec::new(),
+            ..saved_in_v1()
+        });
+        assert_envelope_roundtrip(&character());
+    }
+
+    #[cfg(all(feature = "serde", feature = "bitcode"))]
+    #[test]
+    fn full_characters_round_trip_through_json() {
+        // Temp effects are scene-scoped and never saved to JSON.
+        assert_json_roundtrip(&Character {
+            temp_effects: Vec::new(),
+            ..saved_in_v1()
+        });
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn v1_layout_matches_todays_encoding() {
+        let bytes = include_bytes!("../tests/fixtures/saves/v1_character.bin");
+        assert_eq!(bitcode::encode(&saved_in_v1()), bytes[HEADER_LEN..]);
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn bare_bitcode_from_0_3_2_loads() {
+        let bytes = include_bytes!("../tests/fixtures/saves/v0_character.bin");
+        let loaded = Character::from_envelope_bytes(bytes).unwrap();
+        assert_ne!(loaded.id(), 0);
+        assert_eq!(
+            loaded,
+            Character {
+                id: loaded.id(),
+                ..saved_by_0_3_2()
+            }
+        );
+        assert_eq!(
+            Character::from_envelope_bytes(bytes).unwrap().id(),
+            loaded.id()
+        );
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn bare_bitcode_from_before_inventory_loads() {
+        let bytes = include_bytes!("../tests/fixtures/saves/v0_pre_inventory_character.bin");
+        let loaded = Character::from_envelope_bytes(bytes).unwrap();
+        assert_eq!(
+            loaded,
+            Character {
+                id: loaded.id(),
+                inventory: Vec::new(),
+                ..saved_by_0_3_2()
+            }
+        );
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn data_that_is_no_save_is_bad_magic() {
+        assert!(matches!(
+            Character::from_envelope_bytes(b"not a save"),
+            Err(ArrataError::Envelope(EnvelopeError::BadMagic(_)))
+        ));
+    }
+
+    #[test]
+    fn adversarial_stat_strings_fall_back_without_panicking() {
+        let fallback = |text: &str| {
+            let stat = Stat::from(text.to_string());
+            (stat.quality, stat.quantity)
+        };
+        assert_eq!(fallback("A3"), (Quality::Adept, 3));
+        assert_eq!(fallback("é4"), (Quality::Basic, 4));
+        assert_eq!(fallback("😀2"), (Quality::Basic, 2));
+        assert_eq!(fallback("S😀"), (Quality::Superb, 1));
+        assert_eq!(fallback("A-3"), (Quality::Adept, 1));
+        assert_eq!(fallback("B99999999999999999999999"), (Quality::Basic, 1));
+        assert_eq!(fallback(""), (Quality::Basic, 1));
+        assert_eq!(fallback("\u{200b}"), (Quality::Basic, 1));
+        assert_eq!(fallback("a\u{0663}"), (Quality::Adept, 1));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn temp_effects_are_never_loaded_from_json() {
+        let mut json = serde_json::to_value(character()).unwrap();
+        assert!(json.get("temp_effects").is_none());
+        let loaded: Character = serde_json::from_value(json.clone()).unwrap();
+        assert!(loaded.temp_effects.is_empty());
+
+        json["temp_effects"] = serde_json::json!([{
+            "stat": "Will",
+            "quality_shift": 1,
+            "quantity_delta": 2,
+            "expires_in": 3
+        }]);
+        let loaded: Character = serde_json::from_value(json).unwrap();
+        assert!(loaded.temp_effects.is_empty());
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn temp_effects_are_left_out_of_envelopes() {
+        let plain = character();
+        let mut character = plain.clone();
+        character
+            .temp_effects
+            .push(TempEffect::new("Will".into(), 1, 2, 3));
+        let bytes = character.to_envelope_bytes().unwrap();
+        assert_eq!(bytes, plain.to_envelope_bytes().unwrap());
+        assert!(Character::from_envelope_bytes(&bytes)
+            .unwrap()
+            .temp_effects
+            .is_empty());
+    }
+
+    #[test]
+    fn quirk_balance_reports_list_every_quirk() {
+        let mut character = character();
+        character.quirks.push(Quirk {
+            name: "Gambler".into(),
+            ..quirk(
+                &["Advantage on Will.", "+1 Forte."],
+                &["Can't resist a bet."],
+            )
+        });
+        character.quirks.push(Quirk {
+            name: "Cursed".into(),
+            ..quirk(&[], &["Disadvantage on Speed."])
+        });
+        let report = character.quirk_balance_report();
+        assert_eq!(report.net(), 1);
+        assert_eq!(
+            report.to_string(),
+            "Gambler: 2 boon(s) weighing 5, 1 flaw(s) weighing 1, net +4\n\
+             Cursed: 0 boon(s) weighing 0, 1 flaw(s) weighing 3, net -3\n\
+             Total: net +1"
+        );
+        assert_eq!(
+            Character::new("Bo".into())
+                .quirk_balance_report()
+                .to_string(),
+            "Total: net +0"
+        );
+    }
+
+    #[test]
+    fn dedup_merges_same_named_quirks() {
+        let mut character = character();
+        character.quirks = vec![
+            Quirk {
+                name: "Stubborn".into(),
+                ..quirk(&["Advantage on Will."], &[])
+            },
+            Quirk {
+                name: "Honest".into(),
+                ..quirk(&[], &[])
+            },
+            Quirk {
+                name: "STUBBORN".into(),
+                ..quirk(&["+1 Forte."], &["Pride"])
+            },
+        ];
+        assert_eq!(character.dedup_quirks(), Ok(1));
+        let names: Vec<&str> = character.quirks.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, ["Stubborn", "Honest"]);
+        assert_eq!(
+            character.quirks[0].boons,
+            ["Advantage on Will.", "+1 Forte."]
+        );
+        assert_eq!(character.quirks[0].flaws, ["Pride"]);
+        assert_eq!(character.dedup_quirks(), Ok(0));
+    }
+
+    #[test]
+    fn dedup_rejects_conflicting_categories() {
+        let mut character = character();
+        character.quirks = vec![
+            Quirk {
+                name: "Stubborn".into(),
+                ..quirk(&[], &[])
+            },
+            Quirk {
+                name: "Stubborn".into(),
+                category: QuirkCategory::Pathos,
+                ..quirk(&[], &[])
+            },
+            Quirk {
+                name: "stubborn".into(),
+                ..quirk(&[], &[])
+            },
+        ];
+        let original = character.quirks.clone();
+        assert_eq!(
+            character.dedup_quirks(),
+            Err(QuirkMergeError::CategoryMismatch(
+                QuirkCategory::Ethos,
+                QuirkCategory::Pathos
+            ))
+        );
+        assert_eq!(character.quirks, original);
+    }
+
+    /// A character whose markdown exercises escaping, badges, and
+    /// grouping by category.
+    fn reference_sheet() -> Character {
+        let mut character = character();
+        character.quirks = vec![
+            Quirk {
+                name: "Honest".into(),
+                category: QuirkCategory::Logos,
+                ..quirk(&[], &["Can't lie."])
+            },
+            Quirk {
+                name: "*Bold* [x]|y".into(),
+                description: "Acts_first, asks <later>.".into(),
+                ..quirk(&["Advantage on Will."], &["-1 Speed."])
+            },
+            Quirk {
+                name: "Stubborn".into(),
+                category: QuirkCategory::Logos,
+                ..quirk(&[], &[])
+            },
+            Quirk {
+                name: "Bold, xy".into(),
+                ..quirk(&[], &[])
+            },
+        ];
+        character
+    }
+
+    #[test]
+    fn quirk_markdown_snapshot() {
+        assert_eq!(
+            reference_sheet().quirks_to_markdown(),
+            r#"## Ethos
+
+<a id="bold-xy"></a>
+### \*Bold\* \[x\]\|y
+
+`Ethos`
+
+Acts\_first, asks \<later\>.
+
+**Boons**
+
+- Advantage on Will.
+
+**Flaws**
+
+- -1 Speed.
+
+<a id="bold-xy-2"></a>
+### Bold, xy
+
+`Ethos`
+
+## Logos
+
+<a id="honest"></a>
+### Honest
+
+`Logos`
+
+**Flaws**
+
+- Can't lie.
+
+<a id="stubborn"></a>
+### Stubborn
+
+`Logos`
+"#
+        );
+    }
+
+    #[test]
+    fn sheet_markdown_snapshot() {
+        let mut character = reference_sheet();
+        character.name = "Ash_the *Bold*".into();
+        character.stock = "Human|Elf".into();
+        character.stats[1].checks = None;
+        character.skills.push(Stat {
+            quality: Quality::Adept,
+            quantity: 2,
+            ..Stat::new("Climbing".into())
+        });
+        character
+            .resources
+            .push(Resource::new(Stat::new("Wealth".into()), true));
+        character
+            .resources
+            .push(Resource::new(Stat::new("Friends".into()), false));
+        character.inspiration.logos = 2;
+        character.inventory.push(Item {
+            quantity: 2,
+            description: "Fifty feet,\nknotted.".into(),
+            ..Item::new("Rope".into())
+        });
+        character.argos = Argos::from("Find the sea\nKeep [the] oath");
+        assert_eq!(
+            character.to_markdown(),
+            r#"# Ash\_the \*Bold\*
+
+*Human\|Elf*
+
+## Stats
+
+| Stat | Value | Checks |
+| --- | --- | --- |
+| Will | B4 | 0 |
+| Perception | B1 | - |
+| Conscious | B1 | 0 |
+| Power | B1 | 0 |
+| Speed | B1 | 0 |
+| Forte | B1 | 0 |
+
+## Skills
+
+| Skill | Value | Checks |
+| --- | --- | --- |
+| Climbing | A2 | 0 |
+
+## Resources
+
+- **Wealth** B1 (finite)
+- **Friends** B1
+
+## Inspiration
+
+- Ethos: 0
+- Pathos: 0
+- Logos: 2
+
+## Quirks
+
+### Ethos
+
+<a id="bold-xy"></a>
+#### \*Bold\* \[x\]\|y
+
+`Ethos`
+
+Acts\_first, asks \<later\>.
+
+**Boons**
+
+- Advantage on Will.
+
+**Flaws**
+
+- -1 Speed.
+
+<a id="bold-xy-2"></a>
+#### Bold, xy
+
+`Ethos`
+
+### Logos
+
+<a id="honest"></a>
+#### Honest
+
+`Logos`
+
+**Flaws**
+
+- Can't lie.
+
+<a id="stubborn"></a>
+#### Stubborn
+
+`Logos`
+
+## Inventory
+
+| Item | Quantity | Description |
+| --- | --- | --- |
+| Rope | 2 | Fifty feet, knotted. |
+
+## Argos
+
+- Find the sea
+- Keep \[the\] oath
+"#
+        );
+
+        let bare = Character::new("Bo".into()).to_markdown();
+        assert!(bare.starts_with("# Bo\n\n*Stock*\n\n## Stats\n"));
+        assert!(bare.ends_with("## Inspiration\n\n- Ethos: 0\n- Pathos: 0\n- Logos: 0\n"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn legacy_imports_fill_in_renamed_and_missing_fields() {
+        let legacy = serde_json::json!({
+            "name": "Ash",
+            "stock": null,
+            "stats": [{"name": "Will", "quality": "a"}, {"name": "Power"}],
+            "quirks": [{"name": "Stubborn", "type": "pathos"}, {"name": "Odd"}],
+            "inventory": [{"name": "Rope"}],
+            "argos": "Find the sea\nKeep the oath",
+        });
+        let character = Character::import_legacy(legacy).unwrap();
+
+        assert_eq!(character.name, "Ash");
+        assert_eq!(character.stock, "Stock");
+        assert_eq!(
+            character.stats,
+            [
+                Stat {
+                    quality: Quality::Adept,
+                    quantity: 1,
+                    checks: None,
+                    ..Stat::new("Will".into())
+                },
+                Stat {
+                    checks: None,
+                    ..Stat::new("Power".into())
+                },
+            ]
+        );
+        assert_eq!(
+            character.quirks,
+            [
+                Quirk {
+                    category: QuirkCategory::Pathos,
+                    ..Quirk::new("Stubborn".into())
+                },
+                Quirk {
+                    category: QuirkCategory::Uncategorized,
+                    ..Quirk::new("Odd".into())
+                },
+            ]
+        );
+        assert_eq!(character.inventory, [Item::new("Rope".into())]);
+        assert_eq!(&*character.argos, ["Find the sea", "Keep the oath"]);
+        assert_ne!(character.id(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn legacy_imports_reject_unknown_qualities() {
+        for legacy in [
+            serde_json::json!({"stats": [{"name": "Will", "quality": "Legendary"}]}),
+            serde_json::json!({"skills": [{"name": "Climbing", "quality": "Legendary"}]}),
+            serde_json::json!({"resources": [{"stat": {"name": "Wealth", "quality": "Legendary"}}]}),
+        ] {
+            assert!(matches!(
+                Character::import_legacy(legacy),
+                Err(ArrataError::QualityParse(QualityParseError(quality))) if quality == "Legendary"
+            ));
+        }
+    }
+
+    #[test]
+    fn advancing_moves_up_a_tier_at_the_cap() {
+        for (before, after) in [
+            ("B5", (Quality::Basic, 6)),
+            ("B6", (Quality::Adept, 1)),
+            ("A6", (Quality::Superb, 1)),
+            ("S5", (Quality::Superb, 6)),
+        ] {
+            let mut stat = Stat::from(before.to_string());
+            stat.checks = Some(3);
+            assert!(stat.advance(), "{before}");
+            assert_eq!((stat.quality, stat.quantity), after, "{before}");
+            assert_eq!(stat.checks, Some(3));
+        }
+
+        let mut capped = Stat::from("S6".to_string());
+        assert!(!capped.advance());
+        assert_eq!((capped.quality, capped.quantity), (Quality::Superb, 6));
+    }
+
+    #[test]
+    fn added_items_top_up_stacks_then_start_new_ones() {
+        let mut sheet = character();
+        sheet.inventory.clear();
+        let arrows = |quantity| Item {
+            quantity,
+            max_stack: Some(20),
+            ..Item::new("Arrow".into())
+        };
+
+        assert_eq!(sheet.add_item(arrows(15)), 0);
+        assert_eq!(
+            sheet.add_item(Item {
+                name: "ARROW".into(),
+                ..arrows(30)
+            }),
+            0
+        );
+        let stacks: Vec<usize> = sheet.inventory.iter().map(|item| item.quantity).collect();
+        assert_eq!(stacks, [20, 20, 5]);
+        assert_eq!(sheet.inventory[2].name, "ARROW");
+
+        // Filling the last stack exactly doesn't start an empty one.
+        assert_eq!(sheet.add_item(arrows(15)), 0);
+        assert_eq!(sheet.inventory.len(), 3);
+        assert_eq!(sheet.inventory[2].quantity, 20);
+    }
+
+    #[test]
+    fn unstackable_items_are_returned() {
+        let mut sheet = character();
+        sheet.inventory.clear();
+        let relic = Item {
+            quantity: 2,
+            max_stack: Some(0),
+            ..Item::new("Relic".into())
+        };
+        assert_eq!(sheet.add_item(relic.clone()), 2);
+        assert!(sheet.inventory.is_empty());
+
+        // Full stacks of the same name take nothing either.
+        sheet.inventory.push(Item {
+            quantity: 1,
+            max_stack: Some(1),
+            ..Item::new("Relic".into())
+        });
+        assert_eq!(sheet.add_item(relic), 2);
+        assert_eq!(sheet.inventory.len(), 1);
+    }
+
+    #[test]
+    fn building_from_valid_parts() {
+        let scout = Quirk {
+            prerequisites: vec![Prerequisite::Stock("elf".into())],
+            ..Quirk::new("Scout".into())
+        };
+        let built = Character::from_parts(
+            "Ash".into(),
+            "Elf".into(),
+            vec![Stat::new("Will".into())],
+            vec![Stat::new("Stealth".into())],
+            vec![scout.clone()],
+            vec![Item::new("Rope".into())],
+        )
+        .unwrap();
+        assert_eq!(built.name, "Ash");
+        assert_eq!(built.stock, "Elf");
+        assert_eq!(built.skills, [Stat::new("Stealth".into())]);
+        assert_eq!(built.quirks, [scout]);
+        assert_eq!(built.inventory, [Item::new("Rope".into())]);
+        assert_eq!(built.inspiration, Inspiration::new());
+        assert!(built.macros.is_empty());
+    }
+
+    #[test]
+    fn building_from_invalid_parts_lists_every_issue() {
+        let build = |name: &str, skill: &str, quirks: Vec<Quirk>| {
+            Character::from_parts(
+                name.into(),
+                "Human".into(),
+                vec![Stat::new("Will".into())],
+                vec![Stat::new(skill.into())],
+                quirks,
+                Vec::new(),
+            )
+            .unwrap_err()
+            .issues
+        };
+        let scout = Quirk {
+            prerequisites: vec![Prerequisite::Stock("Elf".into())],
+            ..Quirk::new("Scout".into())
+        };
+        let unmet = ValidationIssue::UnmetPrerequisite {
+            quirk: "Scout".into(),
+            unmet: UnmetPrerequisite {
+                prerequisite: Prerequisite::Stock("Elf".into()),
+            },
+        };
+
+        assert_eq!(
+            build(" ", "Stealth", Vec::new()),
+            [ValidationIssue::EmptyName]
+        );
+        assert_eq!(
+            build("Ash", "WILL", Vec::new()),
+            [ValidationIssue::DuplicateStat("Will".into())]
+        );
+        assert_eq!(
+            build("Ash", "Stealth", vec![scout.clone()]),
+            vec![unmet.clone()]
+        );
+        assert_eq!(
+            build("", "will", vec![scout]),
+            [
+                ValidationIssue::EmptyName,
+                ValidationIssue::DuplicateStat("Will".into()),
+                unmet,
+            ]
+        );
+    }
+
+    #[test]
+    fn stats_parse_from_tsv() {
+        let stats = stats_from_tsv("Power\tA4\n\n  \nFinesse \t s2\n").unwrap();
+        let parsed: Vec<(&str, Quality, usize)> = stats
+            .iter()
+            .map(|stat| (stat.name.as_str(), stat.quality, stat.quantity))
+            .collect();
+        assert_eq!(
+            parsed,
+            [
+                ("Power", Quality::Adept, 4),
+                ("Finesse", Quality::Superb, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_tsv_rows_name_their_line() {
+        // Blank lines still count towards the line number.
+        for (input, line, kind) in [
+            ("Power\tA4\n\nFinesse", 3, StatParseErrorKind::MissingValue),
+            ("Power\tA4\n\t B2", 2, StatParseErrorKind::EmptyName),
+            (
+                "Power\tA4\nFinesse\tX9\nWill\tQ",
+                2,
+                StatParseErrorKind::InvalidValue("X9".into()),
+            ),
+            ("Power\tA", 1, StatParseErrorKind::InvalidValue("A".into())),
+        ] {
+            assert_eq!(
+                stats_from_tsv(input),
+                Err(StatParseError { line, kind }),
+                "{input:?}"
+            );
+        }
+        assert_eq!(
+            stats_from_tsv("\n\nWill\tA-1").unwrap_err().to_string(),
+            "line 3: `A-1` is not a stat like `B4`"
+        );
+    }
+
+    #[test]
+    fn quirks_are_counted_in_category_order() {
+        let mut sheet = character();
+        assert_eq!(sheet.quirk_category_counts(), [0; 4]);
+        for category in [
+            QuirkCategory::Logos,
+            QuirkCategory::Ethos,
+            QuirkCategory::Logos,
+            QuirkCategory::Uncategorized,
+            QuirkCategory::Logos,
+        ] {
+            sheet.quirks.push(Quirk {
+                category,
+                ..Quirk::new("Test".into())
+            });
+        }
+        assert_eq!(sheet.quirk_category_counts(), [1, 0, 3, 1]);
     }
 }
@@ -0,0 +1,122 @@
+//! # Version 0
+//! The [`Character`] layouts saved as bare bitcode, before saves had
+//! an [envelope](crate::serialization::envelope).
+//!
+//! Frozen: never change this file. These saves carry no version, so
+//! they're told apart by which layout decodes: [`Character`] as of
+//! 0.3.2, then [`PreInventoryCharacter`] from before `inventory` was
+//! added.
+//!
+//! Types whose layout hadn't changed by version 1 are shared with
+//! [`v1`](super::v1), which is just as frozen.
+
+use alloc::{string::String, vec::Vec};
+
+use bitcode::{Decode, Encode};
+
+use super::v1::{upgrade, Inspiration, QuirkCategory, Resource, Stat};
+
+/// A character as saved by 0.3.2.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Character {
+    pub name: String,
+    pub stock: String,
+    pub stats: Vec<Stat>,
+    pub skills: Vec<Stat>,
+    pub resources: Vec<Resource>,
+    pub quirks: Vec<Quirk>,
+    pub inspiration: Inspiration,
+    pub argos: String,
+    pub inventory: Vec<Item>,
+}
+
+/// A character as saved before `inventory` was added.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct PreInventoryCharacter {
+    pub name: String,
+    pub stock: String,
+    pub stats: Vec<Stat>,
+    pub skills: Vec<Stat>,
+    pub resources: Vec<Resource>,
+    pub quirks: Vec<Quirk>,
+    pub inspiration: Inspiration,
+    pub argos: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Quirk {
+    pub name: String,
+    pub category: QuirkCategory,
+    pub description: String,
+    pub boons: Vec<String>,
+    pub flaws: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Item {
+    pub name: String,
+    pub quantity: usize,
+    pub description: String,
+}
+
+impl From<Character> for super::Character {
+    /// Newer fields start empty. The id is left at zero, for the
+    /// caller to fill in.
+    fn from(old: Character) -> Self {
+        super::Character {
+            id: 0,
+            name: old.name,
+            stock: old.stock,
+            stats: upgrade(old.stats),
+            skills: upgrade(old.skills),
+            resources: upgrade(old.resources),
+            quirks: upgrade(old.quirks),
+            inspiration: old.inspiration.into(),
+            argos: crate::Argos::from(old.argos.as_str()),
+            inventory: upgrade(old.inventory),
+            macros: Vec::new(),
+            inspiration_log: Vec::new(),
+            temp_effects: Vec::new(),
+        }
+    }
+}
+
+impl From<PreInventoryCharacter> for Character {
+    fn from(old: PreInventoryCharacter) -> Self {
+        Character {
+            name: old.name,
+            stock: old.stock,
+            stats: old.stats,
+            skills: old.skills,
+            resources: old.resources,
+            quirks: old.quirks,
+            inspiration: old.inspiration,
+            argos: old.argos,
+            inventory: Vec::new(),
+        }
+    }
+}
+
+impl From<Quirk> for crate::Quirk {
+    fn from(old: Quirk) -> Self {
+        crate::Quirk {
+            name: old.name,
+            category: old.category.into(),
+            description: old.description,
+            boons: old.boons,
+            flaws: old.flaws,
+            ..crate::Quirk::new(String::new())
+        }
+    }
+}
+
+impl From<Item> for crate::Item {
+    fn from(old: Item) -> Self {
+        crate::Item {
+            name: old.name,
+            quantity: old.quantity,
+            description: old.description,
+            max_stack: None,
+        }
+    }
+}
@@ -0,0 +1,270 @@
+//! # Version 1
+//! The [`Character`] layout stored in version 1 envelopes.
+//!
+//! Frozen: never change this file. When [`super::Character`]'s
+//! fields, or those of any type nested in it, change, bump
+//! [`FORMAT_VERSION`](crate::serialization::envelope::FORMAT_VERSION),
+//! copy the new layout into a `v2` module, and add `From<v1::...>`
+//! conversions so version 1 saves keep loading.
+//!
+//! Every nested type is copied here too, rather than shared with the
+//! current layout, so a change to one of them can't silently change
+//! what version 1 means.
+
+use alloc::{string::String, vec::Vec};
+
+use bitcode::{Decode, Encode};
+
+/// A character as laid out in version 1 envelopes.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Character {
+    pub id: u128,
+    pub name: String,
+    pub stock: String,
+    pub stats: Vec<Stat>,
+    pub skills: Vec<Stat>,
+    pub resources: Vec<Resource>,
+    pub quirks: Vec<Quirk>,
+    pub inspiration: Inspiration,
+    pub argos: Argos,
+    pub inventory: Vec<Item>,
+    pub macros: Vec<RollMacro>,
+    pub inspiration_log: Vec<InspirationEvent>,
+    pub temp_effects: Vec<TempEffect>,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Stat {
+    pub name: String,
+    pub quality: Quality,
+    pub quantity: usize,
+    pub checks: Option<usize>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Encode, Decode)]
+pub enum Quality {
+    Basic,
+    Adept,
+    Superb,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Resource {
+    pub stat: Stat,
+    pub finite: bool,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Quirk {
+    pub name: String,
+    pub category: QuirkCategory,
+    pub description: String,
+    pub boons: Vec<String>,
+    pub flaws: Vec<String>,
+    pub prerequisites: Vec<Prerequisite>,
+    pub tags: Vec<String>,
+    pub uses_this_session: usize,
+    pub session_notes: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub enum QuirkCategory {
+    Ethos,
+    Pathos,
+    Logos,
+    Uncategorized,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub enum Prerequisite {
+    MinStat(String, Quality, usize),
+    Stock(String),
+    HasQuirk(String),
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Inspiration {
+    pub ethos: usize,
+    pub pathos: usize,
+    pub logos: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Argos(pub Vec<String>);
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct Item {
+    pub name: String,
+    pub quantity: usize,
+    pub description: String,
+    pub max_stack: Option<usize>,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct RollMacro {
+    pub name: String,
+    pub stat: String,
+    pub advantage: usize,
+    pub disadvantage: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct InspirationEvent {
+    pub category: QuirkCategory,
+    pub delta: isize,
+    pub reason: String,
+    pub session: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+pub struct TempEffect {
+    pub stat: String,
+    pub quality_shift: i8,
+    pub quantity_delta: isize,
+    pub expires_in: usize,
+}
+
+/// Converts each element of a frozen list to its current type.
+pub(super) fn upgrade<T: Into<U>, U>(old: Vec<T>) -> Vec<U> {
+    old.into_iter().map(Into::into).collect()
+}
+
+impl From<Character> for super::Character {
+    fn from(old: Character) -> Self {
+        super::Character {
+            id: old.id,
+            name: old.name,
+            stock: old.stock,
+            stats: upgrade(old.stats),
+            skills: upgrade(old.skills),
+            resources: upgrade(old.resources),
+            quirks: upgrade(old.quirks),
+            inspiration: old.inspiration.into(),
+            argos: crate::Argos(old.argos.0),
+            inventory: upgrade(old.inventory),
+            macros: upgrade(old.macros),
+            inspiration_log: upgrade(old.inspiration_log),
+            temp_effects: upgrade(old.temp_effects),
+        }
+    }
+}
+
+impl From<Stat> for crate::Stat {
+    fn from(old: Stat) -> Self {
+        crate::Stat {
+            name: old.name,
+            quality: old.quality.into(),
+            quantity: old.quantity,
+            checks: old.checks,
+        }
+    }
+}
+
+impl From<Quality> for crate::Quality {
+    fn from(old: Quality) -> Self {
+        match old {
+            Quality::Basic => crate::Quality::Basic,
+            Quality::Adept => crate::Quality::Adept,
+            Quality::Superb => crate::Quality::Superb,
+        }
+    }
+}
+
+impl From<Resource> for crate::Resource {
+    fn from(old: Resource) -> Self {
+        crate::Resource::new(old.stat.into(), old.finite)
+    }
+}
+
+impl From<Quirk> for crate::Quirk {
+    fn from(old: Quirk) -> Self {
+        crate::Quirk {
+            name: old.name,
+            category: old.category.into(),
+            description: old.description,
+            boons: old.boons,
+            flaws: old.flaws,
+            prerequisites: upgrade(old.prerequisites),
+            tags: old.tags,
+            uses_this_session: old.uses_this_session,
+            session_notes: old.session_notes,
+        }
+    }
+}
+
+impl From<QuirkCategory> for crate::QuirkCategory {
+    fn from(old: QuirkCategory) -> Self {
+        match old {
+            QuirkCategory::Ethos => crate::QuirkCategory::Ethos,
+            QuirkCategory::Pathos => crate::QuirkCategory::Pathos,
+            QuirkCategory::Logos => crate::QuirkCategory::Logos,
+            QuirkCategory::Uncategorized => crate::QuirkCategory::Uncategorized,
+        }
+    }
+}
+
+impl From<Prerequisite> for crate::Prerequisite {
+    fn from(old: Prerequisite) -> Self {
+        match old {
+            Prerequisite::MinStat(name, quality, quantity) => {
+                crate::Prerequisite::MinStat(name, quality.into(), quantity)
+            }
+            Prerequisite::Stock(stock) => crate::Prerequisite::Stock(stock),
+            Prerequisite::HasQuirk(quirk) => crate::Prerequisite::HasQuirk(quirk),
+        }
+    }
+}
+
+impl From<Inspiration> for crate::Inspiration {
+    fn from(old: Inspiration) -> Self {
+        crate::Inspiration {
+            ethos: old.ethos,
+            pathos: old.pathos,
+            logos: old.logos,
+        }
+    }
+}
+
+impl From<Item> for crate::Item {
+    fn from(old: Item) -> Self {
+        crate::Item {
+            name: old.name,
+            quantity: old.quantity,
+            description: old.description,
+            max_stack: old.max_stack,
+        }
+    }
+}
+
+impl From<RollMacro> for crate::RollMacro {
+    fn from(old: RollMacro) -> Self {
+        crate::RollMacro {
+            name: old.name,
+            stat: old.stat,
+            advantage: old.advantage,
+            disadvantage: old.disadvantage,
+        }
+    }
+}
+
+impl From<InspirationEvent> for crate::InspirationEvent {
+    fn from(old: InspirationEvent) -> Self {
+        crate::InspirationEvent {
+            category: old.category.into(),
+            delta: old.delta,
+            reason: old.reason,
+            session: old.session,
+        }
+    }
+}
+
+impl From<TempEffect> for crate::TempEffect {
+    fn from(old: TempEffect) -> Self {
+        crate::TempEffect::new(
+            old.stat,
+            old.quality_shift,
+            old.quantity_delta,
+            old.expires_in,
+        )
+    }
+}
@@ -66,21 +66,318 @@
 //! With both advantage and disadvantage (!1?1S10):
 //! `(1, 2, 2, 2, 3, 3, 4, 4, 5, 6) -> (1, 2, 2, 2, 3, 3, 4, 4, 5, 6, 6) -> (1, 2, 2, 2, 3, 3, 4, 4, 5, 6, 6, 5) -> 10 Successes`
 
+use alloc::{string::String, vec::Vec};
+
+use core::ops::{Add, AddAssign};
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bitcode")]
+use bitcode::{Decode, Encode};
+
 /// The result of rolling `quantity` dice with a `quality` threshold.
-#[derive(Debug, Clone)]
+///
+/// In JSON this is an object with the fields below; see
+/// `roll_result_schema` for a JSON Schema of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RollResult {
     /// The number of successes. Can be negative
     /// with disadvantage.
     pub successes: isize,
     /// The number of failures.
     pub failures: usize,
-    /// The result of each roll. Will be in the
-    /// range 1-6.
+    /// The result of each roll. Will be in the range
+    /// of the die's faces, 1-6 for a d6.
     pub results: Vec<u8>,
+    /// Indices into `results` of dice that exploded
+    /// with advantage. Each re-roll is the entry
+    /// immediately after its triggering die.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub exploded: Vec<usize>,
+    /// The number of successes disadvantage took away, one for each
+    /// die showing the lowest face.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub penalties: usize,
+}
+
+impl RollResult {
+    /// Pairs each exploding die with its re-roll, in roll order.
+    ///
+    /// A re-roll that explodes again starts the next pair.
+    #[must_use]
+    pub fn reroll_pairs(&self) -> Vec<(u8, u8)> {
+        self.exploded
+            .iter()
+            .filter_map(|&index| Some((*self.results.get(index)?, *self.results.get(index + 1)?)))
+            .collect()
+    }
+
+    /// Whether the roll has at least as many successes as the obstacle.
+    ///
+    /// See [`Obstacle::evaluate`](crate::Obstacle::evaluate) for the margin.
+    #[must_use]
+    pub fn is_success(&self, obstacle: &crate::Obstacle) -> bool {
+        obstacle.evaluate(self).is_success()
+    }
+
+    /// Whether the dice came up successes, and whether disadvantage
+    /// took any of them away.
+    ///
+    /// This tells apart a net zero from dice that all failed and one
+    /// from successes cancelled by disadvantage.
+    #[must_use]
+    pub fn outcome_kind(&self) -> RollKind {
+        let rolled = self
+            .successes
+            .saturating_add_unsigned(self.penalties)
+            .max(0);
+        if rolled == 0 {
+            RollKind::CleanFail
+        } else if self.penalties > 0 {
+            RollKind::Mixed
+        } else {
+            RollKind::CleanSuccess
+        }
+    }
+
+    /// Adds another roll's dice to this one, consuming it.
+    ///
+    /// Successes, failures, and penalties are summed, saturating,
+    /// and the other roll's dice are appended, keeping its explosions
+    /// pointing at the right dice. This is `+=` without building a
+    /// new result, for aggregating many rolls in a loop.
+    pub fn merge_in_place(&mut self, other: RollResult) {
+        let offset = self.results.len();
+        self.successes = self.successes.saturating_add(other.successes);
+        self.failures = self.failures.saturating_add(other.failures);
+        self.penalties = self.penalties.saturating_add(other.penalties);
+        self.results.extend(other.results);
+        self.exploded
+            .extend(other.exploded.into_iter().map(|index| index + offset));
+    }
+}
+
+impl Add for RollResult {
+    type Output = RollResult;
+
+    /// Combines two rolls, as [`RollResult::merge_in_place`].
+    fn add(mut self, other: RollResult) -> RollResult {
+        self.merge_in_place(other);
+        self
+    }
+}
+
+impl AddAssign for RollResult {
+    fn add_assign(&mut self, other: RollResult) {
+        self.merge_in_place(other);
+    }
+}
+
+/// The broad shape of a roll, from [`RollResult::outcome_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RollKind {
+    /// No die came up a success. Disadvantage may still have
+    /// pushed the total below zero.
+    CleanFail,
+    /// Some dice came up successes, but disadvantage took at least
+    /// one success away.
+    Mixed,
+    /// Some dice came up successes, and disadvantage took none away.
+    CleanSuccess,
+}
+
+/// A JSON Schema describing how [`RollResult`] serializes to JSON,
+/// for generating types in other languages.
+#[must_use]
+#[cfg(feature = "serde")]
+pub fn roll_result_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "RollResult",
+        "type": "object",
+        "properties": {
+            "successes": {
+                "description": "The number of successes. Can be negative with disadvantage.",
+                "type": "integer"
+            },
+            "failures": {
+                "description": "The number of failures.",
+                "type": "integer",
+                "minimum": 0
+            },
+            "results": {
+                "description": "The face shown by each die rolled, in roll order, including re-rolls.",
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0, "maximum": 255 }
+            },
+            "exploded": {
+                "description": "Indices into results of dice that exploded with advantage. Each re-roll immediately follows its triggering die.",
+                "type": "array",
+                "items": { "type": "integer", "minimum": 0 },
+                "default": []
+            },
+            "penalties": {
+                "description": "The number of successes disadvantage took away, one for each die showing the lowest face.",
+                "type": "integer",
+                "minimum": 0,
+                "default": 0
+            }
+        },
+        "required": ["successes", "failures", "results"]
+    })
+}
+
+/// A rule deciding what each die is worth.
+///
+/// Implement this to roll with table-specific success rules
+/// via [`roll_stat_with_rule`]. Disadvantage still subtracts
+/// a success for every die showing the lowest face, regardless
+/// of the rule.
+pub trait SuccessRule {
+    /// The number of successes a face is worth against `quality`.
+    /// Faces worth zero or less count as failures.
+    fn evaluate(&self, face: u8, quality: u8) -> i8;
+
+    /// Whether a face is re-rolled when the roll has advantage.
+    ///
+    /// A die is re-rolled at most [`MAX_REROLLS`] times in a row, so
+    /// a rule that always explodes still finishes rolling.
+    fn explodes(&self, face: u8) -> bool;
+
+    /// The faces on each die. Disadvantage takes a success away
+    /// for each die showing the lowest face.
+    fn faces(&self) -> DieFaces {
+        DieFaces::D6
+    }
+}
+
+/// The most times a single die is re-rolled by exploding in a row.
+///
+/// A chain this long has odds of 1 in 6^64 with a d6, so this
+/// only ever stops rules whose [`SuccessRule::explodes`] is always
+/// true. The last re-roll is kept but doesn't explode again.
+pub const MAX_REROLLS: usize = 64;
+
+/// The standard Arrata rule: faces at or above the quality are
+/// a success, and 6s explode with advantage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StandardRule;
+
+impl SuccessRule for StandardRule {
+    fn evaluate(&self, face: u8, quality: u8) -> i8 {
+        i8::from(face >= quality)
+    }
+
+    fn explodes(&self, face: u8) -> bool {
+        face == 6
+    }
+}
+
+/// The range of faces on a die, for exotic dice such as 0–5 or 2–7.
+///
+/// As a [`SuccessRule`], faces at or above the quality are a success
+/// and the highest face explodes with advantage. A die with a single
+/// face never explodes, since every roll would.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DieFaces {
+    /// The lowest face, which triggers disadvantage.
+    pub min: u8,
+    /// The highest face, which explodes with advantage.
+    pub max: u8,
+}
+
+impl DieFaces {
+    /// A standard six-sided die.
+    pub const D6: DieFaces = DieFaces { min: 1, max: 6 };
+
+    /// Creates a die, swapping `min` and `max` if needed.
+    #[must_use]
+    pub fn new(min: u8, max: u8) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    /// Rolls the die once.
+    pub fn roll(&self, rng: &mut impl Rng) -> u8 {
+        rng.gen_range(self.min.min(self.max)..=self.max.max(self.min))
+    }
+}
+
+impl Default for DieFaces {
+    fn default() -> Self {
+        Self::D6
+    }
+}
+
+impl SuccessRule for DieFaces {
+    fn evaluate(&self, face: u8, quality: u8) -> i8 {
+        i8::from(face >= quality)
+    }
+
+    fn explodes(&self, face: u8) -> bool {
+        self.min != self.max && face == self.max.max(self.min)
+    }
+
+    fn faces(&self) -> DieFaces {
+        *self
+    }
+}
+
+/// A modifier applied to every roll made in a scene,
+/// such as disadvantage for fighting in the dark.
+///
+/// Modifiers stack by adding their levels together: a scene with
+/// disadvantage 1 on a roll that already has disadvantage 1 from a
+/// quirk or item is rolled at disadvantage 2. Advantage and
+/// disadvantage are tracked separately and never cancel each other.
+///
+/// Quirk levels come from boons and flaws like "advantage on Will",
+/// and items count through quirks conditioned on carrying them; see
+/// [`crate::Character::roll_modifiers`].
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SceneModifier {
+    pub name: String,
+    pub advantage: usize,
+    pub disadvantage: usize,
+}
+
+impl SceneModifier {
+    #[must_use]
+    pub fn new(name: String, advantage: usize, disadvantage: usize) -> Self {
+        Self {
+            name,
+            advantage,
+            disadvantage,
+        }
+    }
+
+    /// Stacks this modifier on top of existing levels of
+    /// advantage and disadvantage.
+    #[must_use]
+    pub fn stack(&self, advantage: usize, disadvantage: usize) -> (usize, usize) {
+        (
+            advantage.saturating_add(self.advantage),
+            disadvantage.saturating_add(self.disadvantage),
+        )
+    }
 }
 
 /// Rolls a given stat with advantage and disadvantage.
 ///
+/// Pools of more than [`MAX_POOL`] dice roll [`MAX_POOL`] dice.
+///
 /// # Inputs
 ///
 /// `stat: Stat` - The stat to roll.
@@ -92,52 +389,822 @@ pub struct RollResult {
 /// # Outputs
 ///
 /// `DiceResult` - The result of the roll.
+#[cfg(feature = "std")]
 #[must_use]
 pub fn roll_stat(
     stat: &crate::character::Stat,
     advantage: usize,
     disadvantage: usize,
 ) -> RollResult {
-    let mut quantity = stat.quantity;
-    let quality = stat.quality as u8;
+    roll_stat_rules(
+        stat.quality.into(),
+        DieFaces::D6,
+        stat.quantity,
+        advantage,
+        disadvantage,
+    )
+}
+
+/// Cancels advantage and disadvantage against each other level for
+/// level, leaving at most one of them.
+///
+/// By default the two stack instead: `!2?2B4` rolls an extra die
+/// and removes one, explodes 6s, and subtracts 1s. Netted, it's a
+/// plain `B4`, and `!3?1B4` becomes `!2B4`.
+///
+/// # Inputs
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// # Outputs
+///
+/// `(usize, usize)` - The advantage and disadvantage left over.
+#[must_use]
+pub fn normalize_modifiers(advantage: usize, disadvantage: usize) -> (usize, usize) {
+    let overlap = advantage.min(disadvantage);
+    (advantage - overlap, disadvantage - overlap)
+}
+
+/// Rolls a stat after netting advantage against disadvantage, for
+/// tables that cancel them instead of stacking them.
+///
+/// See [`normalize_modifiers`] for how this differs from
+/// [`roll_stat`].
+///
+/// # Inputs
+///
+/// `stat: Stat` - The stat to roll.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// # Outputs
+///
+/// `DiceResult` - The result of the roll.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn roll_stat_netted(
+    stat: &crate::character::Stat,
+    advantage: usize,
+    disadvantage: usize,
+) -> RollResult {
+    let (advantage, disadvantage) = normalize_modifiers(advantage, disadvantage);
+    roll_stat(stat, advantage, disadvantage)
+}
+
+/// Rolls a stat with help from other characters.
+///
+/// Each helper with at least one die lends a single die rolled
+/// at Basic quality, whatever their own quality. Advantage and
+/// disadvantage only apply to the primary pool, not the lent dice.
+/// Lent dice are appended after the primary dice in `results`.
+///
+/// # Inputs
+///
+/// `primary: Stat` - The stat of the character making the roll.
+///
+/// `helpers: &[&Stat]` - The stats of each helping character.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// # Outputs
+///
+/// `DiceResult` - The combined result of the roll.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn roll_with_assist(
+    primary: &crate::character::Stat,
+    helpers: &[&crate::character::Stat],
+    advantage: usize,
+    disadvantage: usize,
+) -> RollResult {
+    let mut result = roll_stat(primary, advantage, disadvantage);
+    let lent = helpers.iter().filter(|helper| helper.quantity > 0).count();
+    result.merge_in_place(roll_stat_rules(
+        crate::character::Quality::Basic.into(),
+        DieFaces::D6,
+        lent,
+        0,
+        0,
+    ));
+    result
+}
 
+/// Rolls `quantity` dice against an arbitrary quality threshold
+/// with advantage and disadvantage.
+///
+/// This is the generalized form of [`roll_stat`] for homebrew
+/// tiers that don't fit Basic, Adept, or Superb, or dice other
+/// than a d6.
+///
+/// # Inputs
+///
+/// `quality: CustomQuality` - The lowest face counted as a success.
+///
+/// `faces: DieFaces` - The faces on each die.
+///
+/// `quantity: usize` - The number of dice to roll.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// # Outputs
+///
+/// `DiceResult` - The result of the roll.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn roll_stat_rules(
+    quality: crate::character::CustomQuality,
+    faces: DieFaces,
+    quantity: usize,
+    advantage: usize,
+    disadvantage: usize,
+) -> RollResult {
+    roll_with(
+        quality.0,
+        quantity,
+        advantage,
+        disadvantage,
+        &faces,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// Rolls a given stat with advantage and disadvantage under
+/// a custom [`SuccessRule`].
+///
+/// # Inputs
+///
+/// `stat: Stat` - The stat to roll.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// `rule: R` - The rule deciding what each die is worth.
+///
+/// # Outputs
+///
+/// `DiceResult` - The result of the roll.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn roll_stat_with_rule<R: SuccessRule>(
+    stat: &crate::character::Stat,
+    advantage: usize,
+    disadvantage: usize,
+    rule: &R,
+) -> RollResult {
+    roll_with(
+        stat.quality.threshold(),
+        stat.quantity,
+        advantage,
+        disadvantage,
+        rule,
+        &mut rand::thread_rng(),
+    )
+}
+
+/// Rolls a given stat with advantage and disadvantage using
+/// a caller-supplied random number generator, so rolls can be
+/// reproduced from a seed.
+///
+/// # Inputs
+///
+/// `stat: Stat` - The stat to roll.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// `rng: impl Rng` - The source of randomness.
+///
+/// # Outputs
+///
+/// `DiceResult` - The result of the roll.
+#[must_use]
+pub fn roll_stat_with_rng(
+    stat: &crate::character::Stat,
+    advantage: usize,
+    disadvantage: usize,
+    rng: &mut impl Rng,
+) -> RollResult {
+    roll_with(
+        stat.quality.threshold(),
+        stat.quantity,
+        advantage,
+        disadvantage,
+        &StandardRule,
+        rng,
+    )
+}
+
+/// Rolls a given stat into a reused buffer, for simulations that
+/// roll millions of times and can't afford an allocation per roll.
+///
+/// `buf` is cleared, then filled with the faces rolled, including
+/// re-rolls from exploding dice.
+///
+/// # Inputs
+///
+/// `stat: Stat` - The stat to roll.
+///
+/// `advantage: usize` - The level of advantage on the roll.
+///
+/// `disadvantage: usize` - The level of disadvantage on the roll.
+///
+/// `buf: Vec<u8>` - The buffer to roll into.
+///
+/// `rng: impl Rng` - The source of randomness.
+///
+/// # Outputs
+///
+/// `(isize, usize)` - The successes and failures rolled.
+pub fn roll_into(
+    stat: &crate::character::Stat,
+    advantage: usize,
+    disadvantage: usize,
+    buf: &mut Vec<u8>,
+    rng: &mut impl Rng,
+) -> (isize, usize) {
+    buf.clear();
+    let (successes, failures, _) = roll_dice(
+        stat.quality.threshold(),
+        stat.quantity,
+        advantage,
+        disadvantage,
+        &StandardRule,
+        rng,
+        buf,
+        |_| {},
+    );
+    (successes, failures)
+}
+
+/// Rolls initiative for each named combatant and returns them
+/// in turn order.
+///
+/// Combatants are sorted by successes, most first. Ties go to
+/// whoever rolled more sixes, and remaining ties keep the order
+/// they were given in.
+///
+/// # Inputs
+///
+/// `rolls: &[(String, &Stat)]` - Each combatant's name and the stat they roll.
+///
+/// `rng: impl Rng` - The source of randomness.
+///
+/// # Outputs
+///
+/// `Vec<(String, RollResult)>` - Each combatant and their roll, in turn order.
+#[must_use]
+pub fn initiative_order(
+    rolls: &[(String, &crate::character::Stat)],
+    rng: &mut impl Rng,
+) -> Vec<(String, RollResult)> {
+    let mut order: Vec<(String, RollResult)> = rolls
+        .iter()
+        .map(|(name, stat)| (name.clone(), roll_stat_with_rng(stat, 0, 0, rng)))
+        .collect();
+    sort_initiative(&mut order);
+    order
+}
+
+/// Sorts rolled combatants into turn order, as [`initiative_order`].
+fn sort_initiative(order: &mut [(String, RollResult)]) {
+    order.sort_by_key(|(_, result)| {
+        let sixes: usize = result
+            .results
+            .iter()
+            .map(|&face| usize::from(face == 6))
+            .sum();
+        core::cmp::Reverse((result.successes, sixes))
+    });
+}
+
+/// The result of [`self_test`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelfTestReport {
+    /// How many rolls were made.
+    pub iterations: usize,
+    /// How many of them met the obstacle.
+    pub successes: usize,
+    /// The exact odds of meeting the obstacle.
+    pub expected_rate: f64,
+    /// The fraction of rolls that met the obstacle.
+    pub observed_rate: f64,
+    /// How far the observed rate may stray from the expected rate.
+    pub tolerance: f64,
+    /// Whether the observed rate was within tolerance.
+    pub passed: bool,
+}
+
+/// Checks that the dice engine behaves, for health checks.
+///
+/// Rolls `B4` against `Ob2` `iterations` times through
+/// [`roll_stat`] and compares the success rate to the exact odds
+/// from [`crate::success_probability`]. The tolerance is four
+/// standard errors, so a healthy engine fails about once in 16,000
+/// runs. A few thousand iterations is plenty; with none, the test
+/// fails.
+///
+/// # Inputs
+///
+/// `iterations: usize` - The number of rolls to make.
+///
+/// # Outputs
+///
+/// `SelfTestReport` - The expected and observed rates, and whether
+/// they agree.
+#[cfg(feature = "std")]
+#[must_use]
+pub fn self_test(iterations: usize) -> SelfTestReport {
+    let stat = crate::character::Stat {
+        quantity: 4,
+        ..crate::character::Stat::new(String::new())
+    };
+    let obstacle = crate::Obstacle(2);
+    let successes = (0..iterations)
+        .filter(|_| roll_stat(&stat, 0, 0).successes >= 2)
+        .count();
+
+    let expected_rate = crate::success_probability(&stat, 0, 0, &obstacle);
+    let count = |n: usize| f64::from(u32::try_from(n).unwrap_or(u32::MAX));
+    let (observed_rate, tolerance) = if iterations == 0 {
+        (0.0, 0.0)
+    } else {
+        (
+            count(successes) / count(iterations),
+            4.0 * (expected_rate * (1.0 - expected_rate) / count(iterations)).sqrt(),
+        )
+    };
+    SelfTestReport {
+        iterations,
+        successes,
+        expected_rate,
+        observed_rate,
+        tolerance,
+        passed: iterations > 0 && (observed_rate - expected_rate).abs() <= tolerance,
+    }
+}
+
+/// The most dice rolled in one pool, after advantage and
+/// disadvantage.
+///
+/// No table rolls anywhere near this many, so larger pools are
+/// clamped to it rather than allocating without bound.
+pub const MAX_POOL: usize = 1_000;
+
+/// The number of dice actually rolled for a pool of `quantity` dice
+/// after advantage adds and disadvantage removes dice.
+///
+/// Advantage is applied before disadvantage, so their extra dice
+/// cancel out: B2 with advantage 3 and disadvantage 5 gains two dice
+/// and loses four, leaving an empty pool.
+///
+/// Returns `None` if disadvantage removes more dice than there are.
+/// Both steps saturate, so huge levels can't overflow.
+pub(crate) fn pool_size(quantity: usize, advantage: usize, disadvantage: usize) -> Option<usize> {
+    quantity
+        .saturating_add(advantage.saturating_sub(1))
+        .checked_sub(disadvantage.saturating_sub(1))
+}
+
+pub(crate) fn roll_with<R: SuccessRule>(
+    quality: u8,
+    quantity: usize,
+    advantage: usize,
+    disadvantage: usize,
+    rule: &R,
+    rng: &mut impl Rng,
+) -> RollResult {
+    let mut results = Vec::new();
+    let mut exploded = Vec::new();
+    let (successes, failures, penalties) = roll_dice(
+        quality,
+        quantity,
+        advantage,
+        disadvantage,
+        rule,
+        rng,
+        &mut results,
+        |index| exploded.push(index),
+    );
+
+    RollResult {
+        successes,
+        failures,
+        results,
+        exploded,
+        penalties,
+    }
+}
+
+/// Rolls dice onto the end of `results`, calling `on_explode` with the
+/// index of each die that explodes, and returns the successes, failures,
+/// and successes lost to disadvantage.
+#[allow(clippy::too_many_arguments)]
+fn roll_dice<R: SuccessRule>(
+    quality: u8,
+    quantity: usize,
+    advantage: usize,
+    disadvantage: usize,
+    rule: &R,
+    rng: &mut impl Rng,
+    results: &mut Vec<u8>,
+    mut on_explode: impl FnMut(usize),
+) -> (isize, usize, usize) {
     let mut successes = 0;
     let mut failures = 0;
+    let mut penalties = 0;
+
+    let Some(quantity) = pool_size(quantity, advantage, disadvantage).map(|q| q.min(MAX_POOL))
+    else {
+        // No dice to roll!
+        return (0, 0, 0);
+    };
+
+    let faces = rule.faces();
+    let faces = DieFaces::new(faces.min, faces.max);
+    results.reserve(quantity);
+
+    for _ in 0..quantity {
+        let mut result = faces.roll(rng);
+        let mut explosions = 0;
+        loop {
+            if disadvantage > 0 && result == faces.min {
+                successes -= 1;
+                penalties += 1;
+            }
+            let value = rule.evaluate(result, quality);
+            successes += isize::from(value);
+            failures += usize::from(value <= 0);
+            results.push(result);
 
-    if advantage > 0 {
-        quantity += advantage - 1;
+            // Re-roll exploding dice immediately so each
+            // re-roll follows the die that triggered it.
+            if advantage > 0 && explosions < MAX_REROLLS && rule.explodes(result) {
+                on_explode(results.len() - 1);
+                explosions += 1;
+                result = faces.roll(rng);
+            } else {
+                break;
+            }
+        }
     }
 
-    if disadvantage > 0 {
-        // No dice to roll!
-        if disadvantage - 1 > quantity {
-            return RollResult {
-                successes: 0,
-                failures: 0,
-                results: Vec::new(),
-            };
+    (successes, failures, penalties)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::Stat;
+
+    struct AlwaysExplodes;
+
+    impl SuccessRule for AlwaysExplodes {
+        fn evaluate(&self, _face: u8, _quality: u8) -> i8 {
+            1
+        }
+
+        fn explodes(&self, _face: u8) -> bool {
+            true
         }
-        quantity -= disadvantage - 1;
     }
 
-    let mut results: Vec<u8> = Vec::with_capacity(quantity);
+    #[test]
+    fn explosion_chains_stop_at_the_limit() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let result = roll_with(4, 2, 1, 0, &AlwaysExplodes, &mut rng);
+        assert_eq!(result.results.len(), 2 * (MAX_REROLLS + 1));
+        assert_eq!(result.exploded.len(), 2 * MAX_REROLLS);
+        assert_eq!(
+            result.successes,
+            isize::try_from(result.results.len()).unwrap()
+        );
+    }
 
-    while quantity > 0 {
-        let result: u8 = (rand::random::<u8>() % 6) + 1;
-        if advantage > 0 && result == 6 {
-            quantity += 1;
-        } else if disadvantage > 0 && result == 1 {
-            successes -= 1;
+    #[test]
+    fn explosion_limit_needs_advantage() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let result = roll_with(4, 3, 0, 0, &AlwaysExplodes, &mut rng);
+        assert_eq!(result.results.len(), 3);
+        assert!(result.exploded.is_empty());
+    }
+
+    #[test]
+    fn two_to_seven_dice_roll_in_range() {
+        let faces = DieFaces::new(7, 2);
+        assert_eq!(faces, DieFaces { min: 2, max: 7 });
+        let mut rng = StdRng::seed_from_u64(1);
+        let result = roll_with(5, 500, 0, 0, &faces, &mut rng);
+        assert_eq!(result.results.len(), 500);
+        for face in 2..=7 {
+            assert!(result.results.contains(&face), "never rolled {face}");
         }
-        successes += isize::from(result >= quality);
-        failures += usize::from(result < quality);
-        results.push(result);
-        quantity -= 1;
+        assert!(result.results.iter().all(|face| (2..=7).contains(face)));
+        let successes = result.results.iter().filter(|face| **face >= 5).count();
+        assert_eq!(result.successes, isize::try_from(successes).unwrap());
     }
 
-    RollResult {
-        successes,
-        failures,
-        results,
+    #[test]
+    fn two_to_seven_dice_explode_on_seven() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let result = roll_with(5, 200, 1, 0, &DieFaces::new(2, 7), &mut rng);
+        let sevens: Vec<usize> = (0..result.results.len())
+            .filter(|&index| result.results[index] == 7)
+            .collect();
+        assert!(!sevens.is_empty());
+        assert_eq!(result.exploded, sevens);
+        assert_eq!(result.results.len(), 200 + sevens.len());
+    }
+
+    #[test]
+    fn two_to_seven_dice_lose_successes_on_two() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let result = roll_with(5, 200, 0, 1, &DieFaces::new(2, 7), &mut rng);
+        let twos = result.results.iter().filter(|face| **face <= 2).count();
+        let successes = result.results.iter().filter(|face| **face >= 5).count();
+        assert!(twos > 0);
+        assert_eq!(result.penalties, twos);
+        assert_eq!(
+            result.successes,
+            isize::try_from(successes).unwrap() - isize::try_from(twos).unwrap()
+        );
+    }
+
+    #[test]
+    fn single_face_dice_never_explode() {
+        let faces = DieFaces::new(4, 4);
+        let mut rng = StdRng::seed_from_u64(4);
+        let result = roll_with(4, 3, 2, 0, &faces, &mut rng);
+        assert_eq!(result.results, [4, 4, 4, 4]);
+        assert!(result.exploded.is_empty());
+        assert_eq!(result.successes, 4);
+    }
+
+    #[test]
+    fn disadvantage_cancels_advantage_dice_before_emptying_the_pool() {
+        assert_eq!(pool_size(2, 3, 5), Some(0));
+        assert_eq!(pool_size(2, 3, 4), Some(1));
+        assert_eq!(pool_size(2, 3, 6), None);
+        assert_eq!(pool_size(2, 0, 3), Some(0));
+        assert_eq!(pool_size(2, 0, 4), None);
+        assert_eq!(pool_size(2, 1, 1), Some(2));
+        assert_eq!(pool_size(usize::MAX, usize::MAX, 0), Some(usize::MAX));
+        assert_eq!(pool_size(0, 0, usize::MAX), None);
+    }
+
+    #[test]
+    fn emptied_pools_roll_nothing() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for (advantage, disadvantage) in [(3, 5), (3, 6), (0, 4), (1, usize::MAX)] {
+            let result = roll_with(4, 2, advantage, disadvantage, &StandardRule, &mut rng);
+            assert_eq!(result.successes, 0, "!{advantage}?{disadvantage}B2");
+            assert!(result.results.is_empty());
+            assert_eq!(result.penalties, 0);
+        }
+        let result = roll_with(4, 2, 3, 4, &StandardRule, &mut rng);
+        assert_eq!(result.results.len() - result.exploded.len(), 1);
+    }
+
+    #[test]
+    fn pools_past_the_maximum_are_clamped() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let result = roll_with(4, MAX_POOL, 0, 0, &StandardRule, &mut rng);
+        assert_eq!(result.results.len(), MAX_POOL);
+        for quantity in [MAX_POOL + 1, usize::MAX] {
+            let result = roll_with(4, quantity, 0, 0, &StandardRule, &mut rng);
+            assert_eq!(result.results.len(), MAX_POOL);
+            assert!(result.successes > 0);
+        }
+        // Exploded dice are extra rolls, not part of the pool.
+        let result = roll_with(4, usize::MAX, usize::MAX, 0, &StandardRule, &mut rng);
+        assert_eq!(result.results.len() - result.exploded.len(), MAX_POOL);
+    }
+
+    fn hand_rolled(
+        results: &[u8],
+        exploded: &[usize],
+        successes: isize,
+        penalties: usize,
+    ) -> RollResult {
+        RollResult {
+            successes,
+            failures: results.len() - exploded.len(),
+            results: results.to_vec(),
+            exploded: exploded.to_vec(),
+            penalties,
+        }
+    }
+
+    #[test]
+    fn merged_rolls_keep_their_reroll_pairs() {
+        let first = hand_rolled(&[6, 5, 2], &[0], 2, 0);
+        let second = hand_rolled(&[3, 6, 6, 1], &[1, 2], 3, 1);
+        let pairs: Vec<(u8, u8)> = first
+            .reroll_pairs()
+            .into_iter()
+            .chain(second.reroll_pairs())
+            .collect();
+        assert_eq!(pairs, [(6, 5), (6, 6), (6, 1)]);
+
+        let mut merged = first.clone();
+        merged.merge_in_place(second.clone());
+        assert_eq!(merged.results, [6, 5, 2, 3, 6, 6, 1]);
+        assert_eq!(merged.exploded, [0, 4, 5]);
+        assert_eq!(merged.reroll_pairs(), pairs);
+        assert_eq!(merged.successes, 5);
+        assert_eq!(merged.failures, 4);
+        assert_eq!(merged.penalties, 1);
+        assert_eq!(first + second, merged);
+    }
+
+    #[test]
+    fn merging_saturates() {
+        let mut total = hand_rolled(&[], &[], isize::MAX, usize::MAX);
+        total.failures = usize::MAX;
+        total += hand_rolled(&[6], &[], 1, 1);
+        assert_eq!(total.successes, isize::MAX);
+        assert_eq!(total.failures, usize::MAX);
+        assert_eq!(total.penalties, usize::MAX);
+
+        let mut total = hand_rolled(&[], &[], isize::MIN, 0);
+        total += hand_rolled(&[1], &[], -1, 1);
+        assert_eq!(total.successes, isize::MIN);
+    }
+
+    #[test]
+    fn initiative_breaks_ties_on_sixes_then_keeps_order() {
+        let mut order: Vec<(String, RollResult)> = [
+            ("Ash", hand_rolled(&[4, 5, 1], &[], 2, 0)),
+            ("Bo", hand_rolled(&[6, 4, 2], &[], 2, 0)),
+            ("Cy", hand_rolled(&[1, 2, 3], &[], 0, 0)),
+            ("Dee", hand_rolled(&[5, 4, 5], &[], 3, 0)),
+            ("Eve", hand_rolled(&[4, 4, 3], &[], 2, 0)),
+            ("Fan", hand_rolled(&[6, 1, 3], &[], 1, 0)),
+        ]
+        .into_iter()
+        .map(|(name, result)| (name.to_string(), result))
+        .collect();
+        sort_initiative(&mut order);
+        let names: Vec<&str> = order.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Dee", "Bo", "Ash", "Eve", "Fan", "Cy"]);
+    }
+
+    #[test]
+    fn initiative_rolls_everyone_once() {
+        let will = Stat::new("Will".into());
+        let rolls: Vec<(String, &Stat)> = ["Ash", "Bo", "Cy", "Dee"]
+            .into_iter()
+            .map(|name| (name.to_string(), &will))
+            .collect();
+        let order = initiative_order(&rolls, &mut StdRng::seed_from_u64(3));
+        let mut names: Vec<&str> = order.iter().map(|(name, _)| name.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["Ash", "Bo", "Cy", "Dee"]);
+        assert!(order
+            .windows(2)
+            .all(|pair| pair[0].1.successes >= pair[1].1.successes));
+        assert_eq!(
+            order,
+            initiative_order(&rolls, &mut StdRng::seed_from_u64(3))
+        );
+    }
+
+    #[test]
+    fn rolling_into_a_reused_buffer_matches_a_fresh_roll() {
+        let stat = Stat {
+            quantity: 5,
+            ..Stat::new("Will".into())
+        };
+        let mut buf = vec![9; 64];
+        for (seed, (advantage, disadvantage)) in (0..).zip([(0, 0), (2, 0), (0, 2), (3, 1)]) {
+            let expected = roll_stat_with_rng(
+                &stat,
+                advantage,
+                disadvantage,
+                &mut StdRng::seed_from_u64(seed),
+            );
+            let (successes, failures) = roll_into(
+                &stat,
+                advantage,
+                disadvantage,
+                &mut buf,
+                &mut StdRng::seed_from_u64(seed),
+            );
+            assert_eq!(successes, expected.successes, "!{advantage}?{disadvantage}");
+            assert_eq!(failures, expected.failures, "!{advantage}?{disadvantage}");
+            assert_eq!(buf, expected.results, "!{advantage}?{disadvantage}");
+        }
+    }
+
+    #[test]
+    fn modifiers_cancel_level_for_level() {
+        for ((advantage, disadvantage), netted) in [
+            ((0, 0), (0, 0)),
+            ((2, 2), (0, 0)),
+            ((3, 1), (2, 0)),
+            ((1, 3), (0, 2)),
+            ((4, 0), (4, 0)),
+            ((0, 4), (0, 4)),
+            ((usize::MAX, 1), (usize::MAX - 1, 0)),
+            ((usize::MAX, usize::MAX), (0, 0)),
+        ] {
+            assert_eq!(
+                normalize_modifiers(advantage, disadvantage),
+                netted,
+                "!{advantage}?{disadvantage}"
+            );
+        }
+    }
+
+    #[test]
+    fn outcome_kinds_tell_cancelled_successes_from_failures() {
+        assert_eq!(
+            hand_rolled(&[2, 3, 4], &[], 0, 0).outcome_kind(),
+            RollKind::CleanFail
+        );
+        // Pushed below zero, but no die came up a success.
+        assert_eq!(
+            hand_rolled(&[1, 2, 3], &[], -1, 1).outcome_kind(),
+            RollKind::CleanFail
+        );
+        assert_eq!(
+            hand_rolled(&[1, 4, 5], &[], 0, 1).outcome_kind(),
+            RollKind::Mixed
+        );
+        assert_eq!(
+            hand_rolled(&[1, 1, 5], &[], -1, 2).outcome_kind(),
+            RollKind::Mixed
+        );
+        assert_eq!(
+            hand_rolled(&[3, 5, 6], &[], 2, 0).outcome_kind(),
+            RollKind::CleanSuccess
+        );
+    }
+
+    #[test]
+    fn helpers_lend_one_basic_die_each() {
+        let primary = Stat {
+            quantity: 2,
+            ..Stat::new("Will".into())
+        };
+        let superb = Stat {
+            quality: crate::Quality::Superb,
+            quantity: 3,
+            ..Stat::new("Will".into())
+        };
+        let adept = Stat {
+            quality: crate::Quality::Adept,
+            quantity: 1,
+            ..Stat::new("Will".into())
+        };
+        let empty = Stat {
+            quantity: 0,
+            ..Stat::new("Will".into())
+        };
+        let helpers = [&superb, &adept, &empty];
+
+        let mut lent_low = false;
+        for _ in 0..200 {
+            let result = roll_with_assist(&primary, &helpers, 1, 0);
+            let lent = &result.results[result.results.len() - 2..];
+            lent_low |= lent.iter().any(|face| (2..4).contains(face));
+            // Lent dice count at Basic and don't explode.
+            let basic = result.results.iter().filter(|face| **face >= 4).count();
+            assert_eq!(result.successes, isize::try_from(basic).unwrap());
+            assert_eq!(result.results.len() - result.exploded.len(), 4);
+            assert!(result
+                .exploded
+                .iter()
+                .all(|&index| index < result.results.len() - 2));
+        }
+        assert!(lent_low, "lent dice never rolled a 2 or 3");
+    }
+
+    #[test]
+    fn self_test_fails_without_rolls() {
+        let report = self_test(0);
+        assert_eq!(report.successes, 0);
+        assert!(report.expected_rate > 0.0);
+        assert!(!report.passed);
+    }
+
+    #[test]
+    fn self_test_passes_a_healthy_engine() {
+        let report = self_test(5_000);
+        assert_eq!(report.iterations, 5_000);
+        assert!(report.tolerance > 0.0);
+        assert!(report.passed, "{report:?}");
     }
 }
@@ -0,0 +1,166 @@
+//! # Error
+//! [`ArrataError`], one error type for the whole crate.
+//!
+//! Each module keeps its own specific errors, and every one of them
+//! converts into an [`ArrataError`], so applications can use `?`
+//! across the crate and still match on the specific error.
+
+#[cfg(feature = "std")]
+use crate::serialization::envelope::EnvelopeError;
+#[cfg(feature = "bitcode")]
+use crate::PatchError;
+use crate::{
+    CharacterError, GraduatedObstacleError, NotationError, ObstacleIssue, ObstacleParseError,
+    QualityParseError, QuirkMergeError, QuirkParseError, RollSpecError, StageCountMismatch,
+    StatParseError, ValidationError,
+};
+
+/// Any error produced by this crate.
+#[derive(Debug)]
+pub enum ArrataError {
+    /// See [`CharacterError`].
+    Character(CharacterError),
+    /// A character failed validation.
+    Validation(ValidationError),
+    /// A stat was malformed.
+    StatParse(StatParseError),
+    /// A quality was malformed.
+    QualityParse(QualityParseError),
+    /// A quirk was malformed.
+    QuirkParse(QuirkParseError),
+    /// Two quirks couldn't be merged.
+    QuirkMerge(QuirkMergeError),
+    /// Roll notation, like `!3B4`, was malformed.
+    RollSpec(RollSpecError),
+    /// Roll-versus-obstacle notation was malformed.
+    Notation(NotationError),
+    /// An obstacle, like `Ob3`, was malformed.
+    ObstacleParse(ObstacleParseError),
+    /// An obstacle was outside the sanity limits.
+    Obstacle(ObstacleIssue),
+    /// A graduated obstacle's bands were invalid.
+    GraduatedObstacle(GraduatedObstacleError),
+    /// A compound obstacle got the wrong number of rolls.
+    StageCountMismatch(StageCountMismatch),
+    /// Malformed JSON, such as a quirk library or suggestion table.
+    #[cfg(feature = "serde")]
+    Json(serde_json::Error),
+    /// A binary save's envelope was malformed or corrupt.
+    #[cfg(feature = "std")]
+    Envelope(EnvelopeError),
+    /// A binary save's bitcode payload was malformed.
+    #[cfg(feature = "bitcode")]
+    Bitcode(bitcode::Error),
+    /// A character patch didn't match the character.
+    #[cfg(feature = "bitcode")]
+    Patch(PatchError),
+}
+
+impl core::fmt::Display for ArrataError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArrataError::Character(error) => error.fmt(f),
+            ArrataError::Validation(error) => error.fmt(f),
+            ArrataError::StatParse(error) => write!(f, "invalid stat: {error}"),
+            ArrataError::QualityParse(error) => write!(f, "invalid quality: {error}"),
+            ArrataError::QuirkParse(error) => write!(f, "invalid quirk: {error}"),
+            ArrataError::QuirkMerge(error) => error.fmt(f),
+            ArrataError::RollSpec(error) => write!(f, "invalid roll: {error}"),
+            ArrataError::Notation(error) => error.fmt(f),
+            ArrataError::ObstacleParse(error) => write!(f, "invalid obstacle: {error}"),
+            ArrataError::Obstacle(error) => error.fmt(f),
+            ArrataError::GraduatedObstacle(error) => error.fmt(f),
+            ArrataError::StageCountMismatch(error) => error.fmt(f),
+            #[cfg(feature = "serde")]
+            ArrataError::Json(error) => write!(f, "invalid JSON: {error}"),
+            #[cfg(feature = "std")]
+            ArrataError::Envelope(error) => error.fmt(f),
+            #[cfg(feature = "bitcode")]
+            ArrataError::Bitcode(error) => write!(f, "invalid bitcode: {error}"),
+            #[cfg(feature = "bitcode")]
+            ArrataError::Patch(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for ArrataError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            ArrataError::Character(error) => Some(error),
+            ArrataError::Validation(error) => Some(error),
+            ArrataError::StatParse(error) => Some(error),
+            ArrataError::QualityParse(error) => Some(error),
+            ArrataError::QuirkParse(error) => Some(error),
+            ArrataError::QuirkMerge(error) => Some(error),
+            ArrataError::RollSpec(error) => Some(error),
+            ArrataError::Notation(error) => Some(error),
+            ArrataError::ObstacleParse(error) => Some(error),
+            ArrataError::Obstacle(error) => Some(error),
+            ArrataError::GraduatedObstacle(error) => Some(error),
+            ArrataError::StageCountMismatch(error) => Some(error),
+            #[cfg(feature = "serde")]
+            ArrataError::Json(error) => Some(error),
+            #[cfg(feature = "std")]
+            ArrataError::Envelope(error) => Some(error),
+            #[cfg(feature = "bitcode")]
+            ArrataError::Bitcode(error) => Some(error),
+            #[cfg(feature = "bitcode")]
+            ArrataError::Patch(error) => Some(error),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($variant:ident($error:ty)),* $(,)?) => {
+        $(
+            impl From<$error> for ArrataError {
+                fn from(error: $error) -> Self {
+                    ArrataError::$variant(error)
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(
+    Character(CharacterError),
+    Validation(ValidationError),
+    StatParse(StatParseError),
+    QualityParse(QualityParseError),
+    QuirkParse(QuirkParseError),
+    QuirkMerge(QuirkMergeError),
+    RollSpec(RollSpecError),
+    Notation(NotationError),
+    ObstacleParse(ObstacleParseError),
+    Obstacle(ObstacleIssue),
+    GraduatedObstacle(GraduatedObstacleError),
+    StageCountMismatch(StageCountMismatch),
+);
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for ArrataError {
+    fn from(error: serde_json::Error) -> Self {
+        ArrataError::Json(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<EnvelopeError> for ArrataError {
+    fn from(error: EnvelopeError) -> Self {
+        ArrataError::Envelope(error)
+    }
+}
+
+#[cfg(feature = "bitcode")]
+impl From<bitcode::Error> for ArrataError {
+    fn from(error: bitcode::Error) -> Self {
+        ArrataError::Bitcode(error)
+    }
+}
+
+#[cfg(feature = "bitcode")]
+impl From<PatchError> for ArrataError {
+    fn from(error: PatchError) -> Self {
+        ArrataError::Patch(error)
+    }
+}
@@ -0,0 +1,329 @@
+//! # FFI
+//! A C ABI for embedding the crate in other engines.
+//!
+//! Characters cross the boundary as opaque pointers, and everything
+//! else as plain data or JSON strings. Every function returns an
+//! [`ArrataStatus`] and writes its result through an out pointer,
+//! which is left untouched on failure. Panics are caught and
+//! reported as [`ArrataStatus::Panic`], so they never unwind into
+//! the caller.
+//!
+//! Anything the library allocates must be freed by the library:
+//! characters with [`arrata_character_free`] and strings with
+//! [`arrata_string_free`].
+//!
+//! Build a shared library with
+//! `cargo rustc --release --features ffi --crate-type cdylib`.
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{dice::pool_size, roll_stat, Character, Quality, Stat, MAX_POOL};
+
+/// The result of an FFI call.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrataStatus {
+    Ok = 0,
+    /// A required pointer was null.
+    NullPointer = 1,
+    /// A string wasn't valid UTF-8.
+    InvalidUtf8 = 2,
+    /// A string wasn't a valid character in JSON.
+    InvalidJson = 3,
+    /// A quality wasn't `B`, `A`, or `S`.
+    InvalidQuality = 4,
+    /// The library panicked or otherwise failed internally. This is
+    /// a bug.
+    Panic = 5,
+    /// A roll would have more than [`MAX_POOL`] dice.
+    PoolTooLarge = 6,
+}
+
+/// A roll's result as plain data.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RollResultC {
+    /// The number of successes. Can be negative with disadvantage.
+    pub successes: i64,
+    pub failures: u64,
+    /// How many dice were rolled, including re-rolls.
+    pub dice: u64,
+    /// How many dice exploded with advantage.
+    pub exploded: u64,
+}
+
+/// Runs `f`, turning a panic into [`ArrataStatus::Panic`].
+fn guard(f: impl FnOnce() -> ArrataStatus) -> ArrataStatus {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(ArrataStatus::Panic)
+}
+
+/// Loads a character from a NUL-terminated JSON string.
+///
+/// On success, `*out` is a new character owned by the caller, to be
+/// freed with [`arrata_character_free`]. `json` is only borrowed.
+///
+/// # Safety
+///
+/// `json` must be null or point to a NUL-terminated string, and
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arrata_character_from_json(
+    json: *const c_char,
+    out: *mut *mut Character,
+) -> ArrataStatus {
+    guard(|| {
+        if json.is_null() || out.is_null() {
+            return ArrataStatus::NullPointer;
+        }
+        let Ok(json) = CStr::from_ptr(json).to_str() else {
+            return ArrataStatus::InvalidUtf8;
+        };
+        let Ok(character) = serde_json::from_str::<Character>(json) else {
+            return ArrataStatus::InvalidJson;
+        };
+        *out = Box::into_raw(Box::new(character));
+        ArrataStatus::Ok
+    })
+}
+
+/// Saves a character as a NUL-terminated JSON string.
+///
+/// On success, `*out` is a new string owned by the caller, to be
+/// freed with [`arrata_string_free`]. `character` is only borrowed.
+///
+/// # Safety
+///
+/// `character` must be null or a pointer from
+/// [`arrata_character_from_json`] that hasn't been freed, and `out`
+/// must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arrata_character_to_json(
+    character: *const Character,
+    out: *mut *mut c_char,
+) -> ArrataStatus {
+    guard(|| {
+        if character.is_null() || out.is_null() {
+            return ArrataStatus::NullPointer;
+        }
+        // JSON escapes NUL, so neither step can fail for a valid
+        // character; a failure here is a bug.
+        let Some(json) = serde_json::to_string(&*character)
+            .ok()
+            .and_then(|json| CString::new(json).ok())
+        else {
+            return ArrataStatus::Panic;
+        };
+        *out = json.into_raw();
+        ArrataStatus::Ok
+    })
+}
+
+/// Frees a character from [`arrata_character_from_json`]. Null is
+/// ignored.
+///
+/// # Safety
+///
+/// `character` must be null or a pointer from
+/// [`arrata_character_from_json`] that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn arrata_character_free(character: *mut Character) {
+    if !character.is_null() {
+        drop(Box::from_raw(character));
+    }
+}
+
+/// Frees a string from [`arrata_character_to_json`]. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a pointer from this library that
+/// hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn arrata_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Rolls a stat with advantage and disadvantage, as [`roll_stat`].
+///
+/// `quality` is the ASCII letter `B`, `A`, or `S`, in either case.
+/// On success the result is written to `*out`. Pools of more than
+/// [`MAX_POOL`] dice, after advantage and disadvantage, are rejected
+/// with [`ArrataStatus::PoolTooLarge`] rather than clamped as
+/// [`roll_stat`] does, so C callers learn of the mistake.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn arrata_roll(
+    quality: c_char,
+    quantity: u32,
+    advantage: u32,
+    disadvantage: u32,
+    out: *mut RollResultC,
+) -> ArrataStatus {
+    guard(|| {
+        if out.is_null() {
+            return ArrataStatus::NullPointer;
+        }
+        let quality = match u8::try_from(quality).unwrap_or(0) {
+            b'B' | b'b' => Quality::Basic,
+            b'A' | b'a' => Quality::Adept,
+            b'S' | b's' => Quality::Superb,
+            _ => return ArrataStatus::InvalidQuality,
+        };
+        let widen = |n: u32| usize::try_from(n).unwrap_or(usize::MAX);
+        let (quantity, advantage, disadvantage) =
+            (widen(quantity), widen(advantage), widen(disadvantage));
+        if pool_size(quantity, advantage, disadvantage).is_some_and(|pool| pool > MAX_POOL) {
+            return ArrataStatus::PoolTooLarge;
+        }
+        let stat = Stat {
+            quality,
+            quantity,
+            ..Stat::new(String::new())
+        };
+        let result = roll_stat(&stat, advantage, disadvantage);
+        let count = |n: usize| u64::try_from(n).unwrap_or(u64::MAX);
+        *out = RollResultC {
+            successes: i64::try_from(result.successes).unwrap_or(i64::MIN),
+            failures: count(result.failures),
+            dice: count(result.results.len()),
+            exploded: count(result.exploded.len()),
+        };
+        ArrataStatus::Ok
+    })
+}
+
+#[cfg(all(test, feature = "ffi"))]
+mod tests {
+    use super::*;
+
+    fn letter(c: u8) -> c_char {
+        c_char::try_from(c).unwrap()
+    }
+
+    #[test]
+    fn huge_pools_are_rejected() {
+        let mut out = RollResultC::default();
+        for (quantity, advantage) in [(u32::MAX, 0), (1_001, 0), (1_000, 2), (1, u32::MAX)] {
+            let status = unsafe { arrata_roll(letter(b'B'), quantity, advantage, 0, &raw mut out) };
+            assert_eq!(status, ArrataStatus::PoolTooLarge);
+        }
+        assert_eq!(out, RollResultC::default());
+
+        let status = unsafe { arrata_roll(letter(b'B'), 1, 0, u32::MAX, &raw mut out) };
+        assert_eq!(status, ArrataStatus::Ok);
+        assert_eq!(out.dice, 0);
+        let status = unsafe { arrata_roll(letter(b'B'), 1_000, 0, 0, &raw mut out) };
+        assert_eq!(status, ArrataStatus::Ok);
+        assert_eq!(out.dice, 1_000);
+    }
+
+    fn to_json(character: *const Character) -> String {
+        let mut json = core::ptr::null_mut();
+        assert_eq!(
+            unsafe { arrata_character_to_json(character, &raw mut json) },
+            ArrataStatus::Ok
+        );
+        let string = unsafe { CStr::from_ptr(json) }
+            .to_str()
+            .unwrap()
+            .to_string();
+        unsafe { arrata_string_free(json) };
+        string
+    }
+
+    #[test]
+    fn characters_round_trip_through_json() {
+        let original = Character::new("Ash".into());
+        let json = CString::new(serde_json::to_string(&original).unwrap()).unwrap();
+
+        let mut character = core::ptr::null_mut();
+        let status = unsafe { arrata_character_from_json(json.as_ptr(), &raw mut character) };
+        assert_eq!(status, ArrataStatus::Ok);
+        assert!(!character.is_null());
+        assert_eq!(unsafe { &*character }, &original);
+
+        let saved = to_json(character);
+        assert_eq!(serde_json::from_str::<Character>(&saved).unwrap(), original);
+        unsafe { arrata_character_free(character) };
+    }
+
+    #[test]
+    fn null_pointers_are_reported() {
+        let json = CString::new("{}").unwrap();
+        let mut character = core::ptr::null_mut();
+        let mut string = core::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                arrata_character_from_json(core::ptr::null(), &raw mut character),
+                ArrataStatus::NullPointer
+            );
+            assert_eq!(
+                arrata_character_from_json(json.as_ptr(), core::ptr::null_mut()),
+                ArrataStatus::NullPointer
+            );
+            assert_eq!(
+                arrata_character_to_json(core::ptr::null(), &raw mut string),
+                ArrataStatus::NullPointer
+            );
+            assert_eq!(
+                arrata_roll(letter(b'B'), 4, 0, 0, core::ptr::null_mut()),
+                ArrataStatus::NullPointer
+            );
+            arrata_character_free(core::ptr::null_mut());
+            arrata_string_free(core::ptr::null_mut());
+        }
+        assert!(character.is_null());
+        assert!(string.is_null());
+
+        let owned = Box::into_raw(Box::new(Character::new("Ash".into())));
+        assert_eq!(
+            unsafe { arrata_character_to_json(owned, core::ptr::null_mut()) },
+            ArrataStatus::NullPointer
+        );
+        unsafe { arrata_character_free(owned) };
+    }
+
+    #[test]
+    fn invalid_strings_are_reported() {
+        let mut character = core::ptr::null_mut();
+        let invalid_utf8 = CString::new(vec![b'{', 0xff, b'}']).unwrap();
+        assert_eq!(
+            unsafe { arrata_character_from_json(invalid_utf8.as_ptr(), &raw mut character) },
+            ArrataStatus::InvalidUtf8
+        );
+        let invalid_json = CString::new(r#"{"name": 3"#).unwrap();
+        assert_eq!(
+            unsafe { arrata_character_from_json(invalid_json.as_ptr(), &raw mut character) },
+            ArrataStatus::InvalidJson
+        );
+        assert!(character.is_null());
+    }
+
+    #[test]
+    fn rolls_need_a_known_quality() {
+        let mut out = RollResultC::default();
+        for quality in [b'X', b'c', b'1', 0, 0x80] {
+            let quality = c_char::from_ne_bytes([quality]);
+            assert_eq!(
+                unsafe { arrata_roll(quality, 4, 0, 0, &raw mut out) },
+                ArrataStatus::InvalidQuality
+            );
+        }
+        assert_eq!(out, RollResultC::default());
+
+        for quality in *b"BbAaSs" {
+            assert_eq!(
+                unsafe { arrata_roll(letter(quality), 4, 0, 0, &raw mut out) },
+                ArrataStatus::Ok
+            );
+            assert_eq!(out.dice, 4);
+            assert_eq!(out.failures + u64::try_from(out.successes).unwrap(), 4);
+        }
+    }
+}
@@ -1,12 +1,86 @@
 #![warn(clippy::all, clippy::pedantic)]
 #![allow(clippy::module_name_repetitions)]
+#![cfg_attr(not(feature = "std"), no_std)]
 //! Utilities for Rust-based Arrata applications.
+//!
+//! ## Features
+//!
+//! | Feature        | Default   | Needs               | Adds |
+//! | -------------- | --------- | ------------------- | ---- |
+//! | `alloc`        | mandatory |                     | Everything else: nearly every type holds a `String` or a `Vec`, so the crate doesn't build without it. |
+//! | `std`          | yes       | `alloc`             | Conveniences that roll with the thread's RNG, like [`roll_stat`] and [`Character::new`], and the [`ProbabilityCache`]. |
+//! | `serde`        | yes       | `std`               | `Serialize` and `Deserialize` for the crate's types, the `with` modules like `obstacle::as_string`, and JSON helpers like `roll_result_schema` and the `from_json` loaders. |
+//! | `bitcode`      | yes       | `std`               | `Encode` and `Decode` for the crate's types, for compact binary saves like [`Character::to_envelope_bytes`], [`CharacterPatch`] for syncing edits, and [`Roster`] streams. |
+//! | `ffi`          | no        | `serde`             | A C ABI in the `ffi` module. |
+//! | `test-support` | no        | `serde`, `bitcode`  | Round-trip assertions in `test_support` for applications' tests. |
+//!
+//! `alloc` is enabled by `std`, so only builds without default
+//! features need to name it. With only `alloc`, the crate is `no_std`
+//! and still has the core types, the dice roller, and the probability
+//! math. Pass your own RNG to functions like [`roll_stat_with_rng`].
+//! Build it with `--no-default-features --features alloc`.
+//!
+//! `tests/no_serde.rs` only builds without `serde`, so running the
+//! tests with `--no-default-features --features alloc` or
+//! `--features std` checks the core types still work without it.
+
+extern crate alloc;
+
+#[cfg(not(feature = "alloc"))]
+compile_error!(
+    "arrata-lib needs the `alloc` feature; build with `--no-default-features --features alloc`"
+);
 
 pub mod character;
 pub use character::*;
 pub mod dice;
 pub use dice::*;
+pub mod error;
+pub use error::ArrataError;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod markdown;
+pub mod notation;
+#[cfg(feature = "std")]
+pub use notation::check_str;
+pub use notation::{
+    roll_notation, CheckSpec, NotationError, NotationResult, RollSpec, RollSpecError,
+};
+#[cfg(feature = "bitcode")]
+pub mod patch;
+#[cfg(feature = "bitcode")]
+pub use patch::{CharacterEdit, CharacterPatch, PatchError};
 pub mod obstacle;
-pub use obstacle::Obstacle;
+pub use obstacle::{
+    group_check, AppliedModifier, CheckOutcome, CompoundObstacle, CompoundOutcome, GradedOutcome,
+    GraduatedObstacle, GraduatedObstacleError, GroupResult, GroupScalingRule, Obstacle,
+    ObstacleIssue, ObstacleLimits, ObstacleModifier, ObstacleOutcome, ObstacleParseError,
+    ObstacleRange, ObstacleRule, ParseWarning, StageCountMismatch, StageOutcome, Streak,
+    StreakTracker, SuggestedObstacle, SuggestionTable,
+};
+pub mod probability;
+pub use probability::*;
 pub mod quirk;
 pub use quirk::*;
+#[cfg(feature = "bitcode")]
+pub mod roster;
+#[cfg(feature = "bitcode")]
+pub use roster::{DecodeError, Roster};
+#[cfg(feature = "std")]
+pub mod serialization;
+#[cfg(any(
+    feature = "test-support",
+    all(test, any(feature = "serde", feature = "bitcode"))
+))]
+pub mod test_support;
+
+/// The most commonly used items, for importing with
+/// `use arrata_lib::prelude::*`.
+pub mod prelude {
+    #[cfg(feature = "std")]
+    pub use crate::{check_str, roll_stat};
+    pub use crate::{
+        roll_notation, roll_stat_with_rng, ArrataError, Character, CheckOutcome, CheckSpec,
+        Obstacle, ObstacleOutcome, Quality, Quirk, QuirkCategory, RollResult, RollSpec, Stat,
+    };
+}
@@ -0,0 +1,100 @@
+//! Helpers shared by the markdown exports.
+
+use alloc::{
+    collections::BTreeSet,
+    format,
+    string::{String, ToString},
+};
+
+/// Escapes text so it renders literally in markdown.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Turns a name into a stable anchor.
+///
+/// ASCII letters and digits are lowercased and kept, each run of
+/// whitespace, `-`, or `_` becomes a single `-`, and everything else
+/// is dropped. Leading and trailing `-` are trimmed. A name with
+/// nothing left becomes `fallback`.
+pub(crate) fn slugify(name: &str, fallback: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    let mut pending_dash = false;
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_dash = false;
+            slug.push(c.to_ascii_lowercase());
+        } else if c.is_whitespace() || c == '-' || c == '_' {
+            pending_dash = true;
+        }
+    }
+    if slug.is_empty() {
+        fallback.to_string()
+    } else {
+        slug
+    }
+}
+
+/// Hands out anchors that are unique within one document.
+///
+/// The first use of a slug is kept as is, and repeats get `-2`, `-3`,
+/// and so on, skipping any suffixed slug already handed out, so two
+/// quirks named `Stubborn` become `stubborn` and `stubborn-2`.
+#[derive(Debug, Default)]
+pub(crate) struct Anchors {
+    used: BTreeSet<String>,
+}
+
+impl Anchors {
+    /// A unique anchor for `slug`, recording it as used.
+    pub(crate) fn claim(&mut self, slug: &str) -> String {
+        let mut anchor = slug.to_string();
+        let mut n = 2;
+        while self.used.contains(&anchor) {
+            anchor = format!("{slug}-{n}");
+            n += 1;
+        }
+        self.used.insert(anchor.clone());
+        anchor
+    }
+}
+
+/// A markdown heading prefix, clamped to levels 1 through 6.
+pub(crate) fn heading(level: u8) -> String {
+    "#".repeat(usize::from(level.clamp(1, 6)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_anchors_get_numbered_suffixes() {
+        let mut anchors = Anchors::default();
+        assert_eq!(anchors.claim("stubborn"), "stubborn");
+        assert_eq!(anchors.claim("stubborn"), "stubborn-2");
+        assert_eq!(anchors.claim("stubborn"), "stubborn-3");
+        assert_eq!(anchors.claim("iron-grip"), "iron-grip");
+    }
+
+    #[test]
+    fn suffixed_anchors_skip_names_already_taken() {
+        let mut anchors = Anchors::default();
+        assert_eq!(anchors.claim("stubborn-2"), "stubborn-2");
+        assert_eq!(anchors.claim("stubborn"), "stubborn");
+        assert_eq!(anchors.claim("stubborn"), "stubborn-3");
+    }
+}
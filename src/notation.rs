@@ -0,0 +1,449 @@
+//! # Notation
+//! Parsing the roll notation described in [`crate::dice`], such as
+//! `!3B4` or `?2A3`, optionally followed by an obstacle to roll
+//! against, as in `!2B4 vs Ob3`.
+//!
+//! Advantage (`!`) and disadvantage (`?`) prefixes come before the
+//! stat and may appear in either order. A prefix without a level,
+//! like `!B4`, is level 1. The obstacle follows `vs`, ignoring case
+//! and whitespace, and may be written `Ob3` or just `3`.
+
+use alloc::string::{String, ToString};
+
+use core::str::FromStr;
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bitcode")]
+use bitcode::{Decode, Encode};
+
+#[cfg(feature = "std")]
+use crate::ArrataError;
+use crate::{
+    dice::roll_with, obstacle::CheckOutcome, Obstacle, ObstacleParseError, Quality, RollResult,
+    StandardRule,
+};
+
+/// A roll written in notation, like `!3B4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RollSpec {
+    pub quality: Quality,
+    pub quantity: usize,
+    pub advantage: usize,
+    pub disadvantage: usize,
+}
+
+impl RollSpec {
+    #[must_use]
+    pub fn new(quality: Quality, quantity: usize) -> Self {
+        Self {
+            quality,
+            quantity,
+            advantage: 0,
+            disadvantage: 0,
+        }
+    }
+
+    /// Rolls the dice.
+    pub fn roll(&self, rng: &mut impl Rng) -> RollResult {
+        roll_with(
+            self.quality.threshold(),
+            self.quantity,
+            self.advantage,
+            self.disadvantage,
+            &StandardRule,
+            rng,
+        )
+    }
+}
+
+impl FromStr for RollSpec {
+    type Err = RollSpecError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(RollSpecError::Empty);
+        }
+
+        let mut advantage = None;
+        let mut disadvantage = None;
+        let mut rest = value;
+        while let Some(prefix) = rest.chars().next().filter(|c| matches!(c, '!' | '?')) {
+            let end = 1 + rest[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - 1);
+            let level = match &rest[1..end] {
+                "" => 1,
+                level => level
+                    .parse()
+                    .map_err(|_| RollSpecError::InvalidLevel(level.to_string()))?,
+            };
+            let slot = if prefix == '!' {
+                &mut advantage
+            } else {
+                &mut disadvantage
+            };
+            if slot.replace(level).is_some() {
+                return Err(RollSpecError::DuplicatePrefix(prefix));
+            }
+            rest = &rest[end..];
+        }
+
+        let mut chars = rest.chars();
+        let quality = match chars.next() {
+            Some('B' | 'b') => Quality::Basic,
+            Some('A' | 'a') => Quality::Adept,
+            Some('S' | 's') => Quality::Superb,
+            _ => return Err(RollSpecError::InvalidQuality(rest.to_string())),
+        };
+        let quantity = chars.as_str();
+        if quantity.is_empty() || !quantity.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(RollSpecError::InvalidQuantity(quantity.to_string()));
+        }
+
+        Ok(RollSpec {
+            quality,
+            quantity: quantity
+                .parse()
+                .map_err(|_| RollSpecError::InvalidQuantity(quantity.to_string()))?,
+            advantage: advantage.unwrap_or(0),
+            disadvantage: disadvantage.unwrap_or(0),
+        })
+    }
+}
+
+impl core::fmt::Display for RollSpec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.advantage > 0 {
+            write!(f, "!{}", self.advantage)?;
+        }
+        if self.disadvantage > 0 {
+            write!(f, "?{}", self.disadvantage)?;
+        }
+        let quality = match self.quality {
+            Quality::Basic => 'B',
+            Quality::Adept => 'A',
+            Quality::Superb => 'S',
+        };
+        write!(f, "{quality}{}", self.quantity)
+    }
+}
+
+/// A roll with an optional obstacle, like `!2B4 vs Ob3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CheckSpec {
+    pub roll: RollSpec,
+    pub obstacle: Option<Obstacle>,
+}
+
+impl CheckSpec {
+    /// Rolls the dice and, if there's an obstacle, resolves them against it.
+    pub fn roll(&self, rng: &mut impl Rng) -> NotationResult {
+        let result = self.roll.roll(rng);
+        match self.obstacle {
+            Some(obstacle) => NotationResult::Check(obstacle.check(result)),
+            None => NotationResult::Roll(result),
+        }
+    }
+}
+
+impl FromStr for CheckSpec {
+    type Err = NotationError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        // Roll notation never contains a `v`, so the first `vs`
+        // is where the obstacle starts.
+        let split = value
+            .as_bytes()
+            .windows(2)
+            .position(|pair| pair.eq_ignore_ascii_case(b"vs"));
+        let (roll, obstacle) = match split {
+            Some(index) => (&value[..index], Some(&value[index + 2..])),
+            None => (value, None),
+        };
+
+        Ok(CheckSpec {
+            roll: roll.parse().map_err(NotationError::Roll)?,
+            obstacle: obstacle
+                .map(str::parse)
+                .transpose()
+                .map_err(NotationError::Obstacle)?,
+        })
+    }
+}
+
+impl core::fmt::Display for CheckSpec {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.roll)?;
+        if let Some(obstacle) = self.obstacle {
+            write!(f, " vs {obstacle}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of rolling a [`CheckSpec`].
+#[derive(Debug, Clone)]
+pub enum NotationResult {
+    /// A roll with no obstacle.
+    Roll(RollResult),
+    /// A roll resolved against an obstacle.
+    Check(CheckOutcome),
+}
+
+impl NotationResult {
+    /// The dice rolled.
+    #[must_use]
+    pub fn result(&self) -> &RollResult {
+        match self {
+            NotationResult::Roll(result) => result,
+            NotationResult::Check(outcome) => &outcome.result,
+        }
+    }
+
+    /// Whether the roll met its obstacle, or `None` if there wasn't one.
+    #[must_use]
+    pub fn is_success(&self) -> Option<bool> {
+        match self {
+            NotationResult::Roll(_) => None,
+            NotationResult::Check(outcome) => Some(outcome.is_success()),
+        }
+    }
+}
+
+/// Parses and rolls notation like `!2B4 vs Ob3` or `?1A6 vs 4`.
+///
+/// # Errors
+///
+/// Returns a [`NotationError`] saying whether the roll or the
+/// obstacle was malformed.
+pub fn roll_notation(notation: &str, rng: &mut impl Rng) -> Result<NotationResult, NotationError> {
+    Ok(notation.parse::<CheckSpec>()?.roll(rng))
+}
+
+/// Parses a roll, like `!2A4`, and an obstacle, like `Ob3`, then
+/// rolls and resolves the check.
+///
+/// This is the one-call path for chat bots:
+/// `check_str("!2A4", "Ob3")`.
+///
+/// # Errors
+///
+/// Returns [`ArrataError::RollSpec`] if the roll is malformed, or
+/// [`ArrataError::ObstacleParse`] if the obstacle is.
+#[cfg(feature = "std")]
+pub fn check_str(roll: &str, obstacle: &str) -> Result<CheckOutcome, ArrataError> {
+    let spec: RollSpec = roll.parse()?;
+    let obstacle: Obstacle = obstacle.parse()?;
+    Ok(obstacle.check(spec.roll(&mut rand::thread_rng())))
+}
+
+/// An error parsing a [`RollSpec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollSpecError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// Advantage or disadvantage was given twice.
+    DuplicatePrefix(char),
+    /// The advantage or disadvantage level wasn't a number.
+    InvalidLevel(String),
+    /// The stat didn't start with `B`, `A`, or `S`.
+    InvalidQuality(String),
+    /// The stat's quantity wasn't a number.
+    InvalidQuantity(String),
+}
+
+impl core::fmt::Display for RollSpecError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RollSpecError::Empty => write!(f, "empty roll"),
+            RollSpecError::DuplicatePrefix(prefix) => write!(f, "`{prefix}` given more than once"),
+            RollSpecError::InvalidLevel(level) => write!(f, "`{level}` is not a level"),
+            RollSpecError::InvalidQuality(value) => {
+                write!(f, "`{value}` is not a stat like `B4`")
+            }
+            RollSpecError::InvalidQuantity(value) => write!(f, "`{value}` is not a quantity"),
+        }
+    }
+}
+
+impl core::error::Error for RollSpecError {}
+
+/// An error parsing roll-versus-obstacle notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotationError {
+    /// The roll, before `vs`, was malformed.
+    Roll(RollSpecError),
+    /// The obstacle, after `vs`, was malformed.
+    Obstacle(ObstacleParseError),
+}
+
+impl core::fmt::Display for NotationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NotationError::Roll(error) => write!(f, "invalid roll: {error}"),
+            NotationError::Obstacle(error) => write!(f, "invalid obstacle: {error}"),
+        }
+    }
+}
+
+impl core::error::Error for NotationError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            NotationError::Roll(error) => Some(error),
+            NotationError::Obstacle(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spec(quality: Quality, quantity: usize, advantage: usize, disadvantage: usize) -> RollSpec {
+        RollSpec {
+            advantage,
+            disadvantage,
+            ..RollSpec::new(quality, quantity)
+        }
+    }
+
+    #[test]
+    fn checks_parse_with_and_without_an_obstacle() {
+        for (text, roll, obstacle) in [
+            ("?1A6 vs 4", spec(Quality::Adept, 6, 0, 1), Some(4)),
+            ("!2B4 VS ob3", spec(Quality::Basic, 4, 2, 0), Some(3)),
+            (
+                "  !2?1s3   vS   Ob0  ",
+                spec(Quality::Superb, 3, 2, 1),
+                Some(0),
+            ),
+            ("?!B4vs2", spec(Quality::Basic, 4, 1, 1), Some(2)),
+            ("b4", spec(Quality::Basic, 4, 0, 0), None),
+            (" !3A2 ", spec(Quality::Adept, 2, 3, 0), None),
+        ] {
+            assert_eq!(
+                text.parse(),
+                Ok(CheckSpec {
+                    roll,
+                    obstacle: obstacle.map(Obstacle::new),
+                }),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn errors_say_which_part_was_malformed() {
+        for (text, error) in [
+            ("", RollSpecError::Empty),
+            ("vs Ob3", RollSpecError::Empty),
+            ("!1!2B4", RollSpecError::DuplicatePrefix('!')),
+            ("?B4 ?2", RollSpecError::InvalidQuantity("4 ?2".into())),
+            ("?1?B4", RollSpecError::DuplicatePrefix('?')),
+            (
+                "!99999999999999999999999B4",
+                RollSpecError::InvalidLevel("99999999999999999999999".into()),
+            ),
+            ("X4 vs 3", RollSpecError::InvalidQuality("X4".into())),
+            ("é4", RollSpecError::InvalidQuality("é4".into())),
+            ("B vs 3", RollSpecError::InvalidQuantity(String::new())),
+            ("B-4", RollSpecError::InvalidQuantity("-4".into())),
+        ] {
+            assert_eq!(
+                text.parse::<CheckSpec>(),
+                Err(NotationError::Roll(error)),
+                "{text:?}"
+            );
+        }
+        for (text, error) in [
+            ("B4 vs", ObstacleParseError::Empty),
+            ("B4 vs Ob", ObstacleParseError::MissingNumber),
+        ] {
+            assert_eq!(
+                text.parse::<CheckSpec>(),
+                Err(NotationError::Obstacle(error)),
+                "{text:?}"
+            );
+        }
+        assert!(matches!(
+            "B4 vs hard".parse::<CheckSpec>(),
+            Err(NotationError::Obstacle(_))
+        ));
+        assert_eq!(
+            "!1!2B4".parse::<CheckSpec>().unwrap_err().to_string(),
+            "invalid roll: `!` given more than once"
+        );
+        assert!("B4 vs Ob"
+            .parse::<CheckSpec>()
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid obstacle: "));
+    }
+
+    #[test]
+    fn specs_round_trip_through_display() {
+        for spec in [
+            spec(Quality::Basic, 4, 0, 0),
+            spec(Quality::Adept, 6, 0, 1),
+            spec(Quality::Superb, 0, 3, 2),
+        ] {
+            assert_eq!(spec.to_string().parse(), Ok(spec));
+            let check = CheckSpec {
+                roll: spec,
+                obstacle: Some(Obstacle::new(3)),
+            };
+            assert_eq!(check.to_string().parse(), Ok(check));
+        }
+        assert_eq!(spec(Quality::Adept, 6, 2, 1).to_string(), "!2?1A6");
+        assert_eq!(
+            CheckSpec {
+                roll: spec(Quality::Basic, 4, 0, 0),
+                obstacle: Some(Obstacle::new(3)),
+            }
+            .to_string(),
+            "B4 vs Ob3"
+        );
+    }
+
+    #[test]
+    fn notation_rolls_resolve_only_with_an_obstacle() {
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+        let roll = roll_notation("B4", &mut rng).unwrap();
+        assert_eq!(roll.is_success(), None);
+        assert_eq!(roll.result().results.len(), 4);
+
+        let check = roll_notation("B4 vs Ob0", &mut rng).unwrap();
+        assert_eq!(check.is_success(), Some(true));
+        assert!(roll_notation("B4 vs Ob", &mut rng).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn check_str_reports_which_argument_was_malformed() {
+        assert!(matches!(
+            check_str("X4", "Ob3"),
+            Err(ArrataError::RollSpec(RollSpecError::InvalidQuality(_)))
+        ));
+        assert!(matches!(
+            check_str("!2A4", "Ob"),
+            Err(ArrataError::ObstacleParse(
+                ObstacleParseError::MissingNumber
+            ))
+        ));
+        assert!(matches!(
+            check_str("X4", "Ob"),
+            Err(ArrataError::RollSpec(_))
+        ));
+        let outcome = check_str("!2A4", "Ob0").unwrap();
+        assert!(outcome.is_success());
+        assert!(outcome.result.results.len() >= 5);
+    }
+}
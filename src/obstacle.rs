@@ -1,12 +1,2003 @@
+//! # Obstacle
+//! Obstacles and resolving rolls against them.
+//!
+//! An obstacle is the number of successes a roll needs, written
+//! `Ob3`. Meeting it exactly is a success.
+//!
+//! ## Ob0
+//!
+//! `Ob0` is allowed and means the task succeeds automatically unless
+//! the roll comes up negative. An empty pool's zero successes meets
+//! it, but a roll at disadvantage that ends on -1 successes fails it
+//! by 1, the same as any other shortfall.
+//!
+//! ## Empty pools
+//!
+//! When disadvantage removes every die, the roll has no results, no
+//! failures, and zero successes. It's resolved like any other roll
+//! with zero successes: it fails every obstacle above `Ob0` by the
+//! obstacle's level, and meets `Ob0`.
+
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use alloc::collections::BTreeMap;
+use core::cmp::Ordering;
+use core::ops::{Add, Sub};
+use core::str::FromStr;
+
+use rand::Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bitcode")]
+use bitcode::{Decode, Encode};
+
+use crate::{roll_stat_with_rng, success_probability, Quality, RollResult, Stat};
+
 /// Represents the minimum value for a roll to succeed.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Serializes as a bare integer. Use `as_string` with
+/// `#[serde(with)]` to store it as `"Ob3"` instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Obstacle(pub usize);
 
+impl Obstacle {
+    /// The highest obstacle [`Obstacle::validate`] accepts. Anything
+    /// above is out of reach of any realistic pool.
+    pub const MAX_REASONABLE: usize = 20;
+
+    #[must_use]
+    pub fn new(level: usize) -> Self {
+        Self(level)
+    }
+
+    /// Checks that the obstacle is no higher than
+    /// [`Obstacle::MAX_REASONABLE`], for loaders of hand-written files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ObstacleIssue`] if the obstacle is too high.
+    pub fn validate(&self) -> Result<(), ObstacleIssue> {
+        self.validate_with(&ObstacleLimits::default())
+    }
+
+    /// Checks the obstacle against custom limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ObstacleIssue`] if the obstacle is too high.
+    pub fn validate_with(&self, limits: &ObstacleLimits) -> Result<(), ObstacleIssue> {
+        if self.0 > limits.max_reasonable {
+            return Err(ObstacleIssue::Unreasonable {
+                level: self.0,
+                max: limits.max_reasonable,
+            });
+        }
+        Ok(())
+    }
+
+    /// Parses an obstacle like [`str::parse`], but warns instead of
+    /// failing when it's above the default limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ObstacleParseError`] if the input isn't an obstacle.
+    pub fn parse_lenient(value: &str) -> Result<(Obstacle, Vec<ParseWarning>), ObstacleParseError> {
+        let obstacle: Obstacle = value.parse()?;
+        let warnings = obstacle
+            .validate()
+            .err()
+            .map(ParseWarning::Unreasonable)
+            .into_iter()
+            .collect();
+        Ok((obstacle, warnings))
+    }
+
+    /// Resolves a roll against this obstacle, keeping the roll.
+    #[must_use]
+    pub fn check(&self, result: RollResult) -> CheckOutcome {
+        CheckOutcome {
+            outcome: self.evaluate(&result),
+            obstacle: *self,
+            result,
+        }
+    }
+
+    /// The chance a stat meets this obstacle on a plain roll, with
+    /// no advantage or disadvantage.
+    ///
+    /// Each die succeeds independently, so this is the binomial tail.
+    /// `Ob0` is always certain, and an obstacle above the stat's
+    /// quantity is impossible. See [`success_probability`] for rolls
+    /// with advantage or disadvantage.
+    #[must_use]
+    pub fn chance_against(&self, stat: &Stat) -> f64 {
+        success_probability(stat, 0, 0, self)
+    }
+
+    /// The obstacle for a group test with `participants` people,
+    /// raised by `rule` for everyone past the first. Saturates at
+    /// `usize::MAX`, and zero participants count as one.
+    #[must_use]
+    pub fn scaled_for_group(&self, participants: usize, rule: GroupScalingRule) -> Obstacle {
+        let extra = participants.saturating_sub(1);
+        match rule {
+            GroupScalingRule::PerParticipant(levels) => *self + extra.saturating_mul(levels),
+            GroupScalingRule::CeilHalf => *self + extra.div_ceil(2),
+        }
+    }
+
+    /// Sets the obstacle from a defender's stat, for reactive tests
+    /// like sneaking past a guard's Perception.
+    #[must_use]
+    pub fn from_stat(stat: &Stat, rule: ObstacleRule) -> Obstacle {
+        match rule {
+            ObstacleRule::HalfQuantityRoundedUp => Obstacle(stat.quantity.div_ceil(2)),
+            ObstacleRule::ExpectedSuccesses => {
+                // Each die succeeds on (7 - threshold) of its 6 faces.
+                let faces = usize::from(7 - stat.quality.threshold());
+                Obstacle(stat.quantity.saturating_mul(faces).saturating_add(3) / 6)
+            }
+            ObstacleRule::FixedPerQuality {
+                basic,
+                adept,
+                superb,
+            } => Obstacle(match stat.quality {
+                Quality::Basic => basic,
+                Quality::Adept => adept,
+                Quality::Superb => superb,
+            }),
+        }
+    }
+
+    /// The obstacle raised or lowered by `delta` levels, stopping at `Ob0`.
+    #[must_use]
+    pub fn modified(&self, delta: isize) -> Obstacle {
+        if delta < 0 {
+            *self - delta.unsigned_abs()
+        } else {
+            *self + delta.unsigned_abs()
+        }
+    }
+
+    /// Chips away at the obstacle with a roll's successes, for
+    /// extended challenges that take several rolls to clear.
+    ///
+    /// The level drops by `successes`, stopping at `Ob0`. Negative
+    /// successes make no progress rather than setting it back.
+    /// Returns whether the obstacle is now cleared, at `Ob0`.
+    pub fn apply_successes(&mut self, successes: isize) -> bool {
+        *self = *self - successes.max(0).unsigned_abs();
+        self.0 == 0
+    }
+
+    /// Compares a roll against this obstacle.
+    ///
+    /// Meeting the obstacle exactly is a success, since the obstacle
+    /// is the minimum number of successes needed. Negative successes
+    /// fail by the full distance, so -1 against `Ob2` fails by 3 and
+    /// -1 against `Ob0` fails by 1. An empty pool has zero successes,
+    /// so it fails anything but `Ob0`.
+    #[must_use]
+    pub fn evaluate(&self, result: &RollResult) -> ObstacleOutcome {
+        self.outcome_for(result.successes)
+    }
+
+    /// Compares a number of successes against this obstacle.
+    fn outcome_for(self, successes: isize) -> ObstacleOutcome {
+        let needed = isize::try_from(self.0).unwrap_or(isize::MAX);
+        match successes.cmp(&needed) {
+            Ordering::Less => ObstacleOutcome::Failed {
+                margin: needed.abs_diff(successes),
+            },
+            Ordering::Equal => ObstacleOutcome::Met,
+            Ordering::Greater => ObstacleOutcome::Exceeded {
+                margin: successes.abs_diff(needed),
+            },
+        }
+    }
+
+    /// Applies each modifier in order, returning the final obstacle
+    /// and the itemized steps for display.
+    ///
+    /// Each step is clamped at `Ob0` on its own, so at `Ob0` a `-1`
+    /// followed by a `+1` ends at `Ob1`.
+    #[must_use]
+    pub fn apply_all(&self, mods: &[ObstacleModifier]) -> (Obstacle, Vec<AppliedModifier>) {
+        let mut current = *self;
+        let applied = mods
+            .iter()
+            .map(|modifier| {
+                let before = current;
+                current = current.modified(modifier.delta);
+                AppliedModifier {
+                    label: modifier.label.clone(),
+                    delta: modifier.delta,
+                    before,
+                    after: current,
+                }
+            })
+            .collect();
+        (current, applied)
+    }
+}
+
+impl Add<usize> for Obstacle {
+    type Output = Obstacle;
+
+    /// Raises the obstacle, saturating at `usize::MAX`.
+    fn add(self, levels: usize) -> Obstacle {
+        Obstacle(self.0.saturating_add(levels))
+    }
+}
+
+impl Sub<usize> for Obstacle {
+    type Output = Obstacle;
+
+    /// Lowers the obstacle, stopping at `Ob0`.
+    fn sub(self, levels: usize) -> Obstacle {
+        Obstacle(self.0.saturating_sub(levels))
+    }
+}
+
+/// How [`Obstacle::from_stat`] turns a stat into an obstacle.
+///
+/// The crate recommends [`ObstacleRule::HalfQuantityRoundedUp`],
+/// which is the default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ObstacleRule {
+    /// Half the stat's quantity, rounded up, so B4, A4, and S4
+    /// are all `Ob2`.
+    #[default]
+    HalfQuantityRoundedUp,
+    /// The average successes the stat would roll, rounded to the
+    /// nearest level, so B4 is `Ob2`, A4 is `Ob3`, and S4 is `Ob3`.
+    ExpectedSuccesses,
+    /// A fixed obstacle for each quality, whatever the quantity.
+    FixedPerQuality {
+        basic: usize,
+        adept: usize,
+        superb: usize,
+    },
+}
+
+/// How [`Obstacle::scaled_for_group`] raises an obstacle for
+/// each participant past the first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum GroupScalingRule {
+    /// A fixed number of levels per extra participant, so with
+    /// `PerParticipant(1)` five people face `Ob+4`.
+    PerParticipant(usize),
+    /// Half the extra participants, rounded up, so two people face
+    /// `Ob+1` and five face `Ob+2`.
+    CeilHalf,
+}
+
+/// How a roll fared against an obstacle, from [`Obstacle::evaluate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ObstacleOutcome {
+    /// Fewer successes than the obstacle, short by `margin`.
+    Failed { margin: usize },
+    /// Exactly as many successes as the obstacle.
+    Met,
+    /// More successes than the obstacle, over by `margin`.
+    Exceeded { margin: usize },
+}
+
+impl ObstacleOutcome {
+    /// Whether the roll met or exceeded the obstacle.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        !matches!(self, ObstacleOutcome::Failed { .. })
+    }
+
+    /// The margin as a signed number: negative on failure, zero
+    /// when met, and positive when exceeded.
+    #[must_use]
+    pub fn margin(&self) -> isize {
+        match *self {
+            ObstacleOutcome::Failed { margin } => {
+                isize::try_from(margin).map_or(isize::MIN, |margin| -margin)
+            }
+            ObstacleOutcome::Met => 0,
+            ObstacleOutcome::Exceeded { margin } => isize::try_from(margin).unwrap_or(isize::MAX),
+        }
+    }
+}
+
+/// A roll resolved against an obstacle, from [`Obstacle::check`].
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub result: RollResult,
+    pub obstacle: Obstacle,
+    pub outcome: ObstacleOutcome,
+}
+
+impl CheckOutcome {
+    /// Whether the roll met or exceeded the obstacle.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.outcome.is_success()
+    }
+}
+
+/// Whether a [`StreakTracker`]'s current run is of successes
+/// or failures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Streak {
+    Success,
+    Failure,
+}
+
+/// Tracks runs of successes and failures across a session, for
+/// "hot hand" and "due for a win" mechanics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StreakTracker {
+    current: Option<(Streak, usize)>,
+}
+
+impl StreakTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extends the current streak, or starts a new one if the check
+    /// went the other way.
+    pub fn record(&mut self, outcome: &CheckOutcome) {
+        let streak = if outcome.is_success() {
+            Streak::Success
+        } else {
+            Streak::Failure
+        };
+        self.current = match self.current {
+            Some((current, length)) if current == streak => {
+                Some((streak, length.saturating_add(1)))
+            }
+            _ => Some((streak, 1)),
+        };
+    }
+
+    /// The current streak and its length.
+    ///
+    /// Before any checks are recorded this is `(Streak::Success, 0)`.
+    #[must_use]
+    pub fn current_streak(&self) -> (Streak, usize) {
+        self.current.unwrap_or((Streak::Success, 0))
+    }
+
+    /// Forgets the current streak, as at the start of a session.
+    pub fn reset(&mut self) {
+        self.current = None;
+    }
+}
+
+/// A labeled change to an obstacle, like `+1` for darkness
+/// or `-1` for the right tools.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ObstacleModifier {
+    pub label: String,
+    pub delta: isize,
+}
+
+impl ObstacleModifier {
+    #[must_use]
+    pub fn new(label: String, delta: isize) -> Self {
+        Self { label, delta }
+    }
+}
+
+/// One step of [`Obstacle::apply_all`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AppliedModifier {
+    pub label: String,
+    pub delta: isize,
+    /// The obstacle before this modifier.
+    pub before: Obstacle,
+    /// The obstacle after this modifier, clamped at `Ob0`.
+    pub after: Obstacle,
+}
+
+impl Default for Obstacle {
+    /// `Ob1`, the baseline for a task worth rolling for.
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl core::fmt::Display for Obstacle {
+    /// Formats as `Ob3`, which [`str::parse`] reads back.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad(&format!("Ob{}", self.0))
+    }
+}
+
+impl FromStr for Obstacle {
+    type Err = ObstacleParseError;
+
+    /// Parses `Ob3`, `ob3`, `OB3`, or a bare `3`, ignoring
+    /// surrounding whitespace.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(ObstacleParseError::Empty);
+        }
+        let number = match value.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("ob") => &value[2..],
+            _ => value,
+        };
+        if number.is_empty() {
+            return Err(ObstacleParseError::MissingNumber);
+        }
+        if !number.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ObstacleParseError::Invalid(value.to_string()));
+        }
+        number
+            .parse()
+            .map(Obstacle)
+            .map_err(|_| ObstacleParseError::TooLarge(value.to_string()))
+    }
+}
+
 impl From<String> for Obstacle {
-    /// Given in the form `Ob|ob{Obstacle Level}`.
-    /// No `name` or `checks` field are accepted.
+    /// Deprecated: use [`str::parse`] instead, which reports
+    /// malformed input.
+    ///
+    /// Anything that doesn't parse falls back to the default, `Ob1`.
     fn from(value: String) -> Self {
-        let quantity = value[2..].parse::<usize>().unwrap_or(1);
-        Obstacle(quantity)
+        value.parse().unwrap_or_default()
+    }
+}
+
+/// Limits for [`Obstacle::validate_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ObstacleLimits {
+    pub max_reasonable: usize,
+}
+
+impl Default for ObstacleLimits {
+    fn default() -> Self {
+        Self {
+            max_reasonable: Obstacle::MAX_REASONABLE,
+        }
+    }
+}
+
+/// A problem with an obstacle, from [`Obstacle::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObstacleIssue {
+    /// The obstacle is above the reasonable maximum.
+    Unreasonable { level: usize, max: usize },
+}
+
+impl core::fmt::Display for ObstacleIssue {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ObstacleIssue::Unreasonable { level, max } => {
+                write!(f, "Ob{level} is above the reasonable maximum of Ob{max}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ObstacleIssue {}
+
+/// A warning from [`Obstacle::parse_lenient`] about an obstacle
+/// that parsed but looks wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseWarning {
+    Unreasonable(ObstacleIssue),
+}
+
+impl core::fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParseWarning::Unreasonable(issue) => write!(f, "{issue}"),
+        }
+    }
+}
+
+/// Serializes an [`Obstacle`] as a string like `"Ob3"`, for
+/// hand-edited scene files. Use with `#[serde(with = "obstacle::as_string")]`.
+///
+/// Deserializing accepts anything [`Obstacle`]'s `FromStr` does,
+/// as well as a bare integer.
+#[cfg(feature = "serde")]
+pub mod as_string {
+    use serde::{de, Deserializer, Serializer};
+
+    use super::Obstacle;
+
+    /// Serializes the obstacle as `"Ob{level}"`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's error, if any.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(obstacle: &Obstacle, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(obstacle)
+    }
+
+    /// Deserializes an obstacle from a string or an integer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string isn't an obstacle or the
+    /// integer doesn't fit in a `usize`.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Obstacle, D::Error> {
+        struct ObstacleVisitor;
+
+        impl de::Visitor<'_> for ObstacleVisitor {
+            type Value = Obstacle;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "an obstacle like \"Ob3\" or an integer")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Obstacle, E> {
+                v.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Obstacle, E> {
+                usize::try_from(v).map(Obstacle).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ObstacleVisitor)
+    }
+}
+
+/// An error parsing an [`Obstacle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObstacleParseError {
+    /// The input was empty or only whitespace.
+    Empty,
+    /// An `Ob` prefix with no number after it.
+    MissingNumber,
+    /// Something other than `Ob` followed by digits.
+    Invalid(String),
+    /// A number too large to represent.
+    TooLarge(String),
+}
+
+impl core::fmt::Display for ObstacleParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ObstacleParseError::Empty => write!(f, "empty obstacle"),
+            ObstacleParseError::MissingNumber => write!(f, "missing a number after `Ob`"),
+            ObstacleParseError::Invalid(value) => {
+                write!(f, "`{value}` is not an obstacle like `Ob3`")
+            }
+            ObstacleParseError::TooLarge(value) => write!(f, "`{value}` is too large"),
+        }
+    }
+}
+
+impl core::error::Error for ObstacleParseError {}
+
+/// A band of possible obstacles for tasks whose difficulty
+/// is resolved at play time, e.g. `Ob3–Ob6`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ObstacleRange {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl ObstacleRange {
+    /// Creates a range, swapping `min` and `max` if needed.
+    #[must_use]
+    pub fn new(min: usize, max: usize) -> Self {
+        Self {
+            min: min.min(max),
+            max: min.max(max),
+        }
+    }
+
+    /// Picks an obstacle in the range, inclusive, uniformly at random.
+    pub fn sample(&self, rng: &mut impl Rng) -> Obstacle {
+        let range = Self::new(self.min, self.max);
+        Obstacle(rng.gen_range(range.min..=range.max))
+    }
+
+    /// The obstacle halfway through the range, rounded down.
+    #[must_use]
+    pub fn midpoint(&self) -> Obstacle {
+        let range = Self::new(self.min, self.max);
+        Obstacle(range.min + (range.max - range.min) / 2)
+    }
+}
+
+impl core::fmt::Display for ObstacleRange {
+    /// Formats as `Ob3–Ob6`, honoring width and alignment.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.pad(&format!("Ob{}–Ob{}", self.min, self.max))
+    }
+}
+
+/// An obstacle with partial-success bands, like "Ob4, but 2+
+/// successes gets you partway".
+///
+/// Each band is a threshold and a label, with thresholds strictly
+/// increasing. Scene files are checked the same way when deserialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "GraduatedObstacleRepr"))]
+pub struct GraduatedObstacle {
+    bands: Vec<(usize, String)>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct GraduatedObstacleRepr {
+    bands: Vec<(usize, String)>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GraduatedObstacleRepr> for GraduatedObstacle {
+    type Error = GraduatedObstacleError;
+
+    fn try_from(repr: GraduatedObstacleRepr) -> Result<Self, Self::Error> {
+        Self::new(repr.bands)
+    }
+}
+
+impl GraduatedObstacle {
+    /// Creates a graduated obstacle from bands in ascending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GraduatedObstacleError`] if there are no bands or
+    /// the thresholds don't strictly increase.
+    pub fn new(bands: Vec<(usize, String)>) -> Result<Self, GraduatedObstacleError> {
+        if bands.is_empty() {
+            return Err(GraduatedObstacleError::Empty);
+        }
+        if let Some(index) = bands.windows(2).position(|pair| pair[0].0 >= pair[1].0) {
+            return Err(GraduatedObstacleError::NotIncreasing(index + 1));
+        }
+        Ok(Self { bands })
+    }
+
+    /// The thresholds and labels, lowest first.
+    #[must_use]
+    pub fn bands(&self) -> &[(usize, String)] {
+        &self.bands
+    }
+
+    /// Finds the highest band a roll reached and the threshold
+    /// of the band after it.
+    #[must_use]
+    pub fn evaluate(&self, result: &RollResult) -> GradedOutcome {
+        let reached = self.bands.partition_point(|(threshold, _)| {
+            isize::try_from(*threshold).is_ok_and(|threshold| threshold <= result.successes)
+        });
+        GradedOutcome {
+            band: reached
+                .checked_sub(1)
+                .map(|index| self.bands[index].clone()),
+            next_threshold: self.bands.get(reached).map(|(threshold, _)| *threshold),
+        }
+    }
+}
+
+impl From<Obstacle> for GraduatedObstacle {
+    /// A single band at the obstacle's level, labeled `Success`.
+    fn from(obstacle: Obstacle) -> Self {
+        Self {
+            bands: vec![(obstacle.0, "Success".to_string())],
+        }
+    }
+}
+
+/// How a roll fared against a [`GraduatedObstacle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GradedOutcome {
+    /// The highest band reached, or `None` if the roll fell
+    /// short of every band.
+    pub band: Option<(usize, String)>,
+    /// The threshold of the next band up, or `None` if the roll
+    /// reached the highest band.
+    pub next_threshold: Option<usize>,
+}
+
+/// An error building a [`GraduatedObstacle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraduatedObstacleError {
+    /// There were no bands.
+    Empty,
+    /// The band at this index isn't above the one before it.
+    NotIncreasing(usize),
+}
+
+impl core::fmt::Display for GraduatedObstacleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            GraduatedObstacleError::Empty => {
+                write!(f, "a graduated obstacle needs at least one band")
+            }
+            GraduatedObstacleError::NotIncreasing(index) => {
+                write!(f, "band {index} isn't above the band before it")
+            }
+        }
+    }
+}
+
+impl core::error::Error for GraduatedObstacleError {}
+
+/// Suggests an obstacle from descriptive tags using the built-in
+/// [`SuggestionTable`].
+///
+/// See [`SuggestionTable::suggest`].
+#[must_use]
+pub fn suggest(tags: &[&str]) -> SuggestedObstacle {
+    SuggestionTable::default().suggest(tags)
+}
+
+/// Maps descriptive tags to obstacle levels, so improvised
+/// obstacles stay consistent.
+///
+/// Base tags like `routine` set the starting level, and modifier
+/// tags like `poor tools` raise or lower it. The default is the
+/// built-in table:
+///
+/// | Base tag    | Level |
+/// | ----------- | ----- |
+/// | routine     | 2     |
+/// | challenging | 3     |
+/// | difficult   | 4     |
+/// | heroic      | 5     |
+///
+/// | Modifier tag  | Delta |
+/// | ------------- | ----- |
+/// | poor tools    | +1    |
+/// | time pressure | +1    |
+/// | unfamiliar    | +1    |
+/// | good tools    | -1    |
+/// | familiar      | -1    |
+///
+/// With no base tag, the base is `default_base`, which is 2.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SuggestionTable {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bases: BTreeMap<String, usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modifiers: BTreeMap<String, isize>,
+    #[cfg_attr(feature = "serde", serde(default = "default_base"))]
+    pub default_base: usize,
+}
+
+fn default_base() -> usize {
+    2
+}
+
+impl Default for SuggestionTable {
+    fn default() -> Self {
+        let bases = [
+            ("routine", 2),
+            ("challenging", 3),
+            ("difficult", 4),
+            ("heroic", 5),
+        ];
+        let modifiers = [
+            ("poor tools", 1),
+            ("time pressure", 1),
+            ("unfamiliar", 1),
+            ("good tools", -1),
+            ("familiar", -1),
+        ];
+        Self {
+            bases: bases
+                .into_iter()
+                .map(|(tag, level)| (tag.to_string(), level))
+                .collect(),
+            modifiers: modifiers
+                .into_iter()
+                .map(|(tag, delta)| (tag.to_string(), delta))
+                .collect(),
+            default_base: default_base(),
+        }
+    }
+}
+
+impl SuggestionTable {
+    /// Loads a table from JSON, replacing the built-in one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not a valid table.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Suggests an obstacle from descriptive tags, ignoring case.
+    ///
+    /// The highest base tag sets the starting level, then each
+    /// modifier tag is applied once, in order, stopping at `Ob0`. Tags
+    /// not in the table are collected in
+    /// [`SuggestedObstacle::unknown`].
+    #[must_use]
+    pub fn suggest(&self, tags: &[&str]) -> SuggestedObstacle {
+        let mut base: Option<(String, usize)> = None;
+        let mut mods = Vec::new();
+        let mut unknown = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if let Some((name, level)) = self
+                .bases
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+            {
+                if base.as_ref().is_none_or(|(_, current)| level > current) {
+                    base = Some((name.clone(), *level));
+                }
+            } else if let Some((name, delta)) = self
+                .modifiers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(tag))
+            {
+                if !mods.iter().any(|m: &ObstacleModifier| m.label == *name) {
+                    mods.push(ObstacleModifier::new(name.clone(), *delta));
+                }
+            } else {
+                unknown.push(tag.to_string());
+            }
+        }
+
+        let (base_tag, level) = match base {
+            Some((name, level)) => (Some(name), level),
+            None => (None, self.default_base),
+        };
+        let (obstacle, steps) = Obstacle(level).apply_all(&mods);
+        SuggestedObstacle {
+            obstacle,
+            base_tag,
+            base: Obstacle(level),
+            steps,
+            unknown,
+        }
+    }
+}
+
+/// An obstacle from [`SuggestionTable::suggest`], with the math
+/// behind it.
+///
+/// Displays as a one-line justification, like
+/// `Ob4 (routine Ob2, +1 poor tools, +1 time pressure)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SuggestedObstacle {
+    pub obstacle: Obstacle,
+    /// The base tag used, or `None` for the table's default.
+    pub base_tag: Option<String>,
+    pub base: Obstacle,
+    pub steps: Vec<AppliedModifier>,
+    /// Tags not found in the table.
+    pub unknown: Vec<String>,
+}
+
+impl core::fmt::Display for SuggestedObstacle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} ({} {}",
+            self.obstacle,
+            self.base_tag.as_deref().unwrap_or("default"),
+            self.base
+        )?;
+        for step in &self.steps {
+            write!(f, ", {:+} {}", step.delta, step.label)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// An extended task made of stages rolled in sequence, like picking
+/// a lock (`Ob2`), crossing a courtyard (`Ob3`), and scaling a wall
+/// (`Ob2`).
+///
+/// Scene files are checked with [`Obstacle::validate`] when
+/// deserialized, so a stage above [`Obstacle::MAX_REASONABLE`] fails
+/// to load.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "CompoundObstacleRepr"))]
+pub struct CompoundObstacle {
+    pub stages: Vec<(String, Obstacle)>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct CompoundObstacleRepr {
+    stages: Vec<(String, Obstacle)>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<CompoundObstacleRepr> for CompoundObstacle {
+    type Error = ObstacleIssue;
+
+    fn try_from(repr: CompoundObstacleRepr) -> Result<Self, Self::Error> {
+        for (_, obstacle) in &repr.stages {
+            obstacle.validate()?;
+        }
+        Ok(Self::new(repr.stages))
+    }
+}
+
+impl CompoundObstacle {
+    #[must_use]
+    pub fn new(stages: Vec<(String, Obstacle)>) -> Self {
+        Self { stages }
+    }
+
+    /// Resolves one roll per stage, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`StageCountMismatch`] if there isn't exactly one
+    /// result per stage.
+    pub fn evaluate_sequence(
+        &self,
+        results: &[RollResult],
+    ) -> Result<CompoundOutcome, StageCountMismatch> {
+        if results.len() != self.stages.len() {
+            return Err(StageCountMismatch {
+                stages: self.stages.len(),
+                results: results.len(),
+            });
+        }
+
+        Ok(self.resolve(results))
+    }
+
+    /// Rolls a stat once for every stage with the same advantage
+    /// and disadvantage, and resolves the whole sequence.
+    ///
+    /// Every stage is rolled, even after one fails.
+    pub fn roll_all(
+        &self,
+        stat: &Stat,
+        advantage: usize,
+        disadvantage: usize,
+        rng: &mut impl Rng,
+    ) -> CompoundOutcome {
+        let results: Vec<RollResult> = self
+            .stages
+            .iter()
+            .map(|_| roll_stat_with_rng(stat, advantage, disadvantage, rng))
+            .collect();
+        self.resolve(&results)
+    }
+
+    /// Resolves results against stages, pairing them up in order.
+    fn resolve(&self, results: &[RollResult]) -> CompoundOutcome {
+        let stages: Vec<StageOutcome> = self
+            .stages
+            .iter()
+            .zip(results)
+            .map(|((label, obstacle), result)| StageOutcome {
+                label: label.clone(),
+                obstacle: *obstacle,
+                outcome: obstacle.evaluate(result),
+            })
+            .collect();
+        CompoundOutcome {
+            first_failure: stages.iter().position(|stage| !stage.outcome.is_success()),
+            total_margin: stages
+                .iter()
+                .map(|stage| stage.outcome.margin())
+                .fold(0, isize::saturating_add),
+            stages,
+        }
+    }
+}
+
+/// How one stage of a [`CompoundObstacle`] went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StageOutcome {
+    pub label: String,
+    pub obstacle: Obstacle,
+    pub outcome: ObstacleOutcome,
+}
+
+/// How a whole [`CompoundObstacle`] went.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CompoundOutcome {
+    /// Each stage's outcome, in order.
+    pub stages: Vec<StageOutcome>,
+    /// The index of the first stage failed, if any.
+    pub first_failure: Option<usize>,
+    /// The sum of every stage's signed margin.
+    /// See [`ObstacleOutcome::margin`].
+    pub total_margin: isize,
+}
+
+impl CompoundOutcome {
+    /// Whether every stage succeeded.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.first_failure.is_none()
+    }
+}
+
+/// The error from [`CompoundObstacle::evaluate_sequence`] when the
+/// number of results doesn't match the number of stages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StageCountMismatch {
+    pub stages: usize,
+    pub results: usize,
+}
+
+impl core::fmt::Display for StageCountMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} result(s) for {} stage(s)", self.results, self.stages)
+    }
+}
+
+impl core::error::Error for StageCountMismatch {}
+
+/// Rolls a group test, pooling everyone's successes against one
+/// obstacle, optionally scaled for the size of the group.
+///
+/// Each participant rolls their stat without advantage or
+/// disadvantage.
+pub fn group_check(
+    participants: &[&Stat],
+    obstacle: &Obstacle,
+    scaling: Option<GroupScalingRule>,
+    rng: &mut impl Rng,
+) -> GroupResult {
+    let scaled = scaling.map_or(*obstacle, |rule| {
+        obstacle.scaled_for_group(participants.len(), rule)
+    });
+    let rolls: Vec<RollResult> = participants
+        .iter()
+        .map(|stat| roll_stat_with_rng(stat, 0, 0, rng))
+        .collect();
+    let successes = rolls
+        .iter()
+        .map(|roll| roll.successes)
+        .fold(0, isize::saturating_add);
+    GroupResult {
+        base: *obstacle,
+        obstacle: scaled,
+        rolls,
+        successes,
+        outcome: scaled.outcome_for(successes),
+    }
+}
+
+/// The result of a [`group_check`].
+#[derive(Debug, Clone)]
+pub struct GroupResult {
+    /// The obstacle before scaling.
+    pub base: Obstacle,
+    /// The obstacle the group rolled against, after scaling.
+    pub obstacle: Obstacle,
+    /// Each participant's roll, in order.
+    pub rolls: Vec<RollResult>,
+    /// Everyone's successes added together.
+    pub successes: isize,
+    pub outcome: ObstacleOutcome,
+}
+
+impl GroupResult {
+    /// Whether the group met or exceeded the obstacle.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.outcome.is_success()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "bitcode")]
+    use crate::test_support::assert_bitcode_roundtrip;
+    #[cfg(feature = "serde")]
+    use crate::test_support::assert_json_roundtrip;
+
+    #[test]
+    fn obstacles_parse_with_or_without_a_prefix() {
+        for text in ["Ob3", "ob3", "OB3", "oB3", "3", "  Ob3\t", "Ob03"] {
+            assert_eq!(text.parse(), Ok(Obstacle(3)), "{text:?}");
+        }
+        assert_eq!("Ob0".parse(), Ok(Obstacle(0)));
+    }
+
+    #[test]
+    fn adversarial_obstacles_are_rejected_without_panicking() {
+        assert_eq!("".parse::<Obstacle>(), Err(ObstacleParseError::Empty));
+        assert_eq!(" \t\n".parse::<Obstacle>(), Err(ObstacleParseError::Empty));
+        assert_eq!(
+            "Ob".parse::<Obstacle>(),
+            Err(ObstacleParseError::MissingNumber)
+        );
+        assert_eq!(
+            " ob ".parse::<Obstacle>(),
+            Err(ObstacleParseError::MissingNumber)
+        );
+        for text in [
+            "x",
+            "O",
+            "Ob 3",
+            "Ob-3",
+            "Ob+3",
+            "+3",
+            "-1",
+            "3.5",
+            "Ob3x",
+            "Obb3",
+            "🎲",
+            "é3",
+            "O😀3",
+            "Ob😀",
+            "3😀",
+            "Ob\u{0663}",
+            "\u{200b}3",
+        ] {
+            assert!(
+                matches!(
+                    text.parse::<Obstacle>(),
+                    Err(ObstacleParseError::Invalid(_))
+                ),
+                "{text:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn huge_obstacles_are_too_large() {
+        let huge = format!("Ob{}0", usize::MAX);
+        assert_eq!(
+            huge.parse::<Obstacle>(),
+            Err(ObstacleParseError::TooLarge(huge))
+        );
+        assert_eq!(
+            format!("{}", usize::MAX).parse::<Obstacle>(),
+            Ok(Obstacle(usize::MAX))
+        );
+        let long = "9".repeat(10_000);
+        assert!(matches!(
+            long.parse::<Obstacle>(),
+            Err(ObstacleParseError::TooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn from_string_falls_back_to_ob1() {
+        assert_eq!(Obstacle::from("Ob4".to_string()), Obstacle(4));
+        for text in ["", "Ob", "x", "🎲🎲", "Ob99999999999999999999999999"] {
+            assert_eq!(Obstacle::from(text.to_string()), Obstacle(1), "{text:?}");
+        }
+    }
+
+    #[test]
+    fn obstacles_display_as_ob_levels() {
+        assert_eq!(Obstacle::new(3).to_string(), "Ob3");
+        assert_eq!(Obstacle::default(), Obstacle::new(1));
+        assert_eq!(Obstacle::default().to_string(), "Ob1");
+        assert_eq!(format!("{:>5}", Obstacle::new(3)), "  Ob3");
+    }
+
+    #[test]
+    fn obstacles_round_trip_through_display_and_parse() {
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+        let levels = (0..=100)
+            .chain([usize::MAX - 1, usize::MAX])
+            .chain((0..1000).map(|_| rng.gen::<usize>()));
+        for level in levels {
+            let obstacle = Obstacle::new(level);
+            assert_eq!(obstacle.to_string().parse(), Ok(obstacle));
+        }
+    }
+
+    #[test]
+    fn obstacles_are_copy_and_hash() {
+        let mut seen = alloc::collections::BTreeSet::new();
+        let obstacle = Obstacle::new(2);
+        let copy = obstacle;
+        seen.insert(obstacle);
+        assert!(seen.contains(&copy));
+        #[cfg(feature = "std")]
+        {
+            let set: std::collections::HashSet<Obstacle> =
+                [obstacle, copy, Obstacle::new(3)].into();
+            assert_eq!(set.len(), 2);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct PlannedObstacle {
+        #[serde(with = "as_string")]
+        obstacle: Obstacle,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn obstacles_serialize_as_integers() {
+        for level in [0, 3, usize::MAX] {
+            let obstacle = Obstacle::new(level);
+            let json = serde_json::to_string(&obstacle).unwrap();
+            assert_eq!(json, level.to_string());
+            assert_json_roundtrip(&obstacle);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn obstacles_serialize_as_strings_with_as_string() {
+        for level in [0, 3, usize::MAX] {
+            let planned = PlannedObstacle {
+                obstacle: Obstacle::new(level),
+            };
+            let json = serde_json::to_string(&planned).unwrap();
+            assert_eq!(json, format!(r#"{{"obstacle":"Ob{level}"}}"#));
+            assert_json_roundtrip(&planned);
+        }
+        let bare: PlannedObstacle = serde_json::from_str(r#"{"obstacle":4}"#).unwrap();
+        assert_eq!(bare.obstacle, Obstacle::new(4));
+        assert!(serde_json::from_str::<PlannedObstacle>(r#"{"obstacle":"Ob"}"#).is_err());
+        assert!(serde_json::from_str::<PlannedObstacle>(r#"{"obstacle":-1}"#).is_err());
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn obstacles_round_trip_through_bitcode() {
+        for level in [0, 3, usize::MAX] {
+            assert_bitcode_roundtrip(&Obstacle::new(level));
+        }
+    }
+
+    fn rolled(successes: isize) -> RollResult {
+        RollResult {
+            successes,
+            failures: 0,
+            results: Vec::new(),
+            exploded: Vec::new(),
+            penalties: 0,
+        }
+    }
+
+    #[test]
+    fn meeting_an_obstacle_exactly_succeeds() {
+        let obstacle = Obstacle::new(3);
+        assert_eq!(
+            obstacle.evaluate(&rolled(2)),
+            ObstacleOutcome::Failed { margin: 1 }
+        );
+        assert_eq!(obstacle.evaluate(&rolled(3)), ObstacleOutcome::Met);
+        assert_eq!(
+            obstacle.evaluate(&rolled(4)),
+            ObstacleOutcome::Exceeded { margin: 1 }
+        );
+        assert!(!obstacle.evaluate(&rolled(2)).is_success());
+        assert!(obstacle.evaluate(&rolled(3)).is_success());
+        assert!(obstacle.evaluate(&rolled(4)).is_success());
+    }
+
+    #[test]
+    fn negative_successes_fail_by_the_full_distance() {
+        assert_eq!(
+            Obstacle::new(2).evaluate(&rolled(-1)),
+            ObstacleOutcome::Failed { margin: 3 }
+        );
+        assert_eq!(
+            Obstacle::new(0).evaluate(&rolled(-1)),
+            ObstacleOutcome::Failed { margin: 1 }
+        );
+        assert_eq!(Obstacle::new(0).evaluate(&rolled(0)), ObstacleOutcome::Met);
+        assert_eq!(Obstacle::new(2).evaluate(&rolled(-1)).margin(), -3);
+    }
+
+    #[test]
+    fn huge_obstacles_fail_without_overflowing() {
+        assert_eq!(
+            Obstacle::new(usize::MAX).evaluate(&rolled(isize::MIN)),
+            ObstacleOutcome::Failed {
+                margin: isize::MAX.abs_diff(isize::MIN)
+            }
+        );
+        assert_eq!(
+            Obstacle::new(0).evaluate(&rolled(isize::MAX)),
+            ObstacleOutcome::Exceeded {
+                margin: isize::MAX.unsigned_abs()
+            }
+        );
+    }
+
+    fn four_dice(quality: Quality) -> Stat {
+        Stat {
+            quality,
+            quantity: 4,
+            ..Stat::new("Perception".into())
+        }
+    }
+
+    #[test]
+    fn obstacles_from_four_dice_stats_are_pinned() {
+        let fixed = ObstacleRule::FixedPerQuality {
+            basic: 1,
+            adept: 2,
+            superb: 4,
+        };
+        for (rule, expected) in [
+            (ObstacleRule::HalfQuantityRoundedUp, [2, 2, 2]),
+            (ObstacleRule::ExpectedSuccesses, [2, 3, 3]),
+            (fixed, [1, 2, 4]),
+        ] {
+            let obstacles = [Quality::Basic, Quality::Adept, Quality::Superb]
+                .map(|quality| Obstacle::from_stat(&four_dice(quality), rule).0);
+            assert_eq!(obstacles, expected, "{rule:?}");
+        }
+    }
+
+    #[test]
+    fn obstacles_from_stats_round_as_documented() {
+        let stat = |quantity| Stat {
+            quantity,
+            ..four_dice(Quality::Basic)
+        };
+        assert_eq!(
+            Obstacle::from_stat(&stat(0), ObstacleRule::default()),
+            Obstacle(0)
+        );
+        assert_eq!(
+            Obstacle::from_stat(&stat(3), ObstacleRule::default()),
+            Obstacle(2)
+        );
+        assert_eq!(
+            Obstacle::from_stat(&stat(usize::MAX), ObstacleRule::default()),
+            Obstacle(usize::MAX.div_ceil(2))
+        );
+        assert_eq!(ObstacleRule::default(), ObstacleRule::HalfQuantityRoundedUp);
+    }
+
+    fn lock() -> GraduatedObstacle {
+        GraduatedObstacle::new(vec![
+            (2, "Partway".to_string()),
+            (4, "Open".to_string()),
+            (6, "Silently".to_string()),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn graduated_obstacles_find_the_highest_band() {
+        let lock = lock();
+        let band = |successes| lock.evaluate(&rolled(successes));
+        assert_eq!(
+            band(-2),
+            GradedOutcome {
+                band: None,
+                next_threshold: Some(2),
+            }
+        );
+        assert_eq!(band(1).band, None);
+        assert_eq!(band(2).band, Some((2, "Partway".to_string())));
+        assert_eq!(band(3).next_threshold, Some(4));
+        assert_eq!(band(5).band, Some((4, "Open".to_string())));
+        assert_eq!(
+            band(6),
+            GradedOutcome {
+                band: Some((6, "Silently".to_string())),
+                next_threshold: None,
+            }
+        );
+        assert_eq!(band(isize::MAX).band, Some((6, "Silently".to_string())));
+    }
+
+    #[test]
+    fn graduated_obstacles_need_increasing_bands() {
+        assert_eq!(
+            GraduatedObstacle::new(Vec::new()),
+            Err(GraduatedObstacleError::Empty)
+        );
+        assert_eq!(
+            GraduatedObstacle::new(vec![(2, "Partway".to_string()), (2, "Open".to_string())]),
+            Err(GraduatedObstacleError::NotIncreasing(1))
+        );
+        assert_eq!(
+            GraduatedObstacle::new(vec![
+                (1, "a".to_string()),
+                (3, "b".to_string()),
+                (2, "c".to_string())
+            ]),
+            Err(GraduatedObstacleError::NotIncreasing(2))
+        );
+    }
+
+    #[test]
+    fn plain_obstacles_become_a_single_band() {
+        let graduated = GraduatedObstacle::from(Obstacle::new(3));
+        assert_eq!(graduated.bands(), [(3, "Success".to_string())]);
+        assert_eq!(graduated.evaluate(&rolled(2)).band, None);
+        assert_eq!(
+            graduated.evaluate(&rolled(3)).band,
+            Some((3, "Success".to_string()))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn graduated_obstacles_are_checked_when_loaded() {
+        assert_json_roundtrip(&lock());
+        assert!(serde_json::from_str::<GraduatedObstacle>(r#"{"bands": []}"#).is_err());
+        assert!(
+            serde_json::from_str::<GraduatedObstacle>(r#"{"bands": [[3, "a"], [1, "b"]]}"#)
+                .is_err()
+        );
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn chance_against_is_the_binomial_tail() {
+        let b4 = four_dice(Quality::Basic);
+        assert_close(Obstacle::new(1).chance_against(&b4), 15.0 / 16.0);
+        assert_close(Obstacle::new(2).chance_against(&b4), 11.0 / 16.0);
+        assert_close(Obstacle::new(4).chance_against(&b4), 1.0 / 16.0);
+        assert_close(
+            Obstacle::new(4).chance_against(&four_dice(Quality::Adept)),
+            16.0 / 81.0,
+        );
+        assert_close(
+            Obstacle::new(4).chance_against(&four_dice(Quality::Superb)),
+            625.0 / 1296.0,
+        );
+    }
+
+    #[test]
+    fn chance_against_pins_the_edges() {
+        for quality in [Quality::Basic, Quality::Adept, Quality::Superb] {
+            for quantity in 1..=6 {
+                let stat = Stat {
+                    quantity,
+                    ..four_dice(quality)
+                };
+                assert_close(Obstacle::new(0).chance_against(&stat), 1.0);
+                assert_close(Obstacle::new(quantity + 1).chance_against(&stat), 0.0);
+            }
+        }
+        assert_close(
+            Obstacle::new(usize::MAX).chance_against(&four_dice(Quality::Superb)),
+            0.0,
+        );
+    }
+
+    #[test]
+    fn built_in_suggestion_table_is_frozen() {
+        let table = SuggestionTable::default();
+        let bases: Vec<(&str, usize)> = table
+            .bases
+            .iter()
+            .map(|(tag, level)| (tag.as_str(), *level))
+            .collect();
+        assert_eq!(
+            bases,
+            [
+                ("challenging", 3),
+                ("difficult", 4),
+                ("heroic", 5),
+                ("routine", 2)
+            ]
+        );
+        let modifiers: Vec<(&str, isize)> = table
+            .modifiers
+            .iter()
+            .map(|(tag, delta)| (tag.as_str(), *delta))
+            .collect();
+        assert_eq!(
+            modifiers,
+            [
+                ("familiar", -1),
+                ("good tools", -1),
+                ("poor tools", 1),
+                ("time pressure", 1),
+                ("unfamiliar", 1)
+            ]
+        );
+        assert_eq!(table.default_base, 2);
+    }
+
+    #[test]
+    fn suggestions_explain_themselves() {
+        let suggested = suggest(&[
+            "Routine",
+            "poor tools",
+            " time pressure ",
+            "poor tools",
+            "rainy",
+        ]);
+        assert_eq!(suggested.obstacle, Obstacle(4));
+        assert_eq!(suggested.base_tag.as_deref(), Some("routine"));
+        assert_eq!(suggested.unknown, ["rainy"]);
+        assert_eq!(
+            suggested.to_string(),
+            "Ob4 (routine Ob2, +1 poor tools, +1 time pressure)"
+        );
+
+        let suggested = suggest(&["routine", "heroic", "familiar"]);
+        assert_eq!(suggested.to_string(), "Ob4 (heroic Ob5, -1 familiar)");
+        assert_eq!(suggest(&[]).to_string(), "Ob2 (default Ob2)");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn suggestion_tables_load_from_json() {
+        let table = SuggestionTable::from_json(
+            r#"{"bases": {"trivial": 0}, "modifiers": {"cursed": 3}, "default_base": 1}"#,
+        )
+        .unwrap();
+        assert_eq!(table.suggest(&["trivial", "cursed"]).obstacle, Obstacle(3));
+        assert_eq!(table.suggest(&["routine"]).unknown, ["routine"]);
+        assert_eq!(table.suggest(&[]).obstacle, Obstacle(1));
+        assert_eq!(SuggestionTable::from_json("{}").unwrap().default_base, 2);
+    }
+
+    #[test]
+    fn ob0_succeeds_unless_the_roll_is_negative() {
+        let ob0 = Obstacle::new(0);
+        assert_eq!(ob0.evaluate(&rolled(0)), ObstacleOutcome::Met);
+        assert_eq!(
+            ob0.evaluate(&rolled(2)),
+            ObstacleOutcome::Exceeded { margin: 2 }
+        );
+        assert_eq!(
+            ob0.evaluate(&rolled(-1)),
+            ObstacleOutcome::Failed { margin: 1 }
+        );
+        assert_eq!(
+            ob0.evaluate(&rolled(-3)),
+            ObstacleOutcome::Failed { margin: 3 }
+        );
+        assert!(!ob0.check(rolled(-1)).outcome.is_success());
+    }
+
+    #[test]
+    fn ob0_against_an_empty_pool_is_met() {
+        let empty = Stat {
+            quantity: 0,
+            ..four_dice(Quality::Basic)
+        };
+        let result =
+            crate::roll_stat_with_rng(&empty, 0, 0, &mut rand::rngs::mock::StepRng::new(0, 1));
+        assert!(result.results.is_empty());
+        assert_eq!(Obstacle::new(0).evaluate(&result), ObstacleOutcome::Met);
+        assert_eq!(
+            Obstacle::new(1).evaluate(&result),
+            ObstacleOutcome::Failed { margin: 1 }
+        );
+        assert!((Obstacle::new(0).chance_against(&empty) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ob0_can_fail_with_disadvantage() {
+        let b1 = Stat {
+            quantity: 1,
+            ..four_dice(Quality::Basic)
+        };
+        // A 1 is -1 success with disadvantage, so Ob0 fails one time in six.
+        let chance = success_probability(&b1, 0, 1, &Obstacle::new(0));
+        assert!((chance - 5.0 / 6.0).abs() < 1e-9, "{chance}");
+    }
+
+    #[test]
+    fn group_scaling_is_pinned_for_one_two_and_five() {
+        let ob2 = Obstacle::new(2);
+        let scaled =
+            |rule| [1, 2, 5].map(|participants| ob2.scaled_for_group(participants, rule).0);
+        assert_eq!(scaled(GroupScalingRule::PerParticipant(1)), [2, 3, 6]);
+        assert_eq!(scaled(GroupScalingRule::PerParticipant(2)), [2, 4, 10]);
+        assert_eq!(scaled(GroupScalingRule::CeilHalf), [2, 3, 4]);
+        assert_eq!(
+            ob2.scaled_for_group(0, GroupScalingRule::CeilHalf),
+            ob2.scaled_for_group(1, GroupScalingRule::CeilHalf)
+        );
+    }
+
+    #[test]
+    fn group_scaling_saturates() {
+        assert_eq!(
+            Obstacle::new(2)
+                .scaled_for_group(usize::MAX, GroupScalingRule::PerParticipant(usize::MAX)),
+            Obstacle::new(usize::MAX)
+        );
+        assert_eq!(
+            Obstacle::new(usize::MAX).scaled_for_group(5, GroupScalingRule::CeilHalf),
+            Obstacle::new(usize::MAX)
+        );
+    }
+
+    #[test]
+    fn group_checks_report_the_scaled_obstacle() {
+        let stats = [
+            four_dice(Quality::Basic),
+            four_dice(Quality::Adept),
+            four_dice(Quality::Superb),
+        ];
+        let participants: Vec<&Stat> = stats.iter().collect();
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0x2545_f491_4f6c_dd1d);
+
+        let result = group_check(
+            &participants,
+            &Obstacle::new(2),
+            Some(GroupScalingRule::PerParticipant(1)),
+            &mut rng,
+        );
+        assert_eq!(result.base, Obstacle::new(2));
+        assert_eq!(result.obstacle, Obstacle::new(4));
+        assert_eq!(result.rolls.len(), 3);
+        let total: isize = result.rolls.iter().map(|roll| roll.successes).sum();
+        assert_eq!(result.successes, total);
+        assert_eq!(result.outcome, result.obstacle.outcome_for(total));
+
+        let unscaled = group_check(&participants, &Obstacle::new(2), None, &mut rng);
+        assert_eq!(unscaled.obstacle, unscaled.base);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn compound_obstacles_are_validated_when_loaded() {
+        let heist: CompoundObstacle =
+            serde_json::from_str(r#"{"stages": [["Lock", 2], ["Courtyard", 3]]}"#).unwrap();
+        assert_eq!(
+            heist.stages,
+            [
+                ("Lock".to_string(), Obstacle(2)),
+                ("Courtyard".to_string(), Obstacle(3))
+            ]
+        );
+
+        let error = serde_json::from_str::<CompoundObstacle>(
+            r#"{"stages": [["Lock", 2], ["Vault", 999]]}"#,
+        )
+        .unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("Ob999 is above the reasonable maximum of Ob20"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn pools_emptied_by_disadvantage_fail_positive_obstacles() {
+        let b2 = Stat {
+            quantity: 2,
+            ..four_dice(Quality::Basic)
+        };
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+        for disadvantage in [3, 4, usize::MAX] {
+            let result = roll_stat_with_rng(&b2, 0, disadvantage, &mut rng);
+            assert!(result.results.is_empty());
+            assert_eq!(result.failures, 0);
+            assert!(result.is_success(&Obstacle::new(0)));
+            for level in [1, 3] {
+                let check = Obstacle::new(level).check(result.clone());
+                assert_eq!(check.outcome, ObstacleOutcome::Failed { margin: level });
+                assert!(!check.is_success());
+                assert!(!result.is_success(&Obstacle::new(level)));
+            }
+        }
+    }
+
+    #[test]
+    fn obstacle_arithmetic_saturates_at_both_ends() {
+        assert_eq!(Obstacle(0) - 1, Obstacle(0));
+        assert_eq!(Obstacle(3) - 1, Obstacle(2));
+        assert_eq!(Obstacle(usize::MAX) + 1, Obstacle(usize::MAX));
+        assert_eq!(Obstacle(3) + 1, Obstacle(4));
+
+        assert_eq!(Obstacle(3).modified(isize::MIN), Obstacle(0));
+        assert_eq!(
+            Obstacle(0).modified(isize::MAX),
+            Obstacle(isize::MAX.unsigned_abs())
+        );
+        assert_eq!(
+            Obstacle(usize::MAX).modified(isize::MAX),
+            Obstacle(usize::MAX)
+        );
+        assert_eq!(Obstacle(3).modified(-2), Obstacle(1));
+        assert_eq!(Obstacle(3).modified(0), Obstacle(3));
+    }
+
+    #[test]
+    fn modifiers_clamp_at_ob0_one_step_at_a_time() {
+        let mods = [
+            ObstacleModifier::new("Cover".into(), -1),
+            ObstacleModifier::new("Rain".into(), 1),
+        ];
+        let (last, applied) = Obstacle(0).apply_all(&mods);
+        assert_eq!(last, Obstacle(1));
+        assert_eq!(
+            applied,
+            [
+                AppliedModifier {
+                    label: "Cover".into(),
+                    delta: -1,
+                    before: Obstacle(0),
+                    after: Obstacle(0),
+                },
+                AppliedModifier {
+                    label: "Rain".into(),
+                    delta: 1,
+                    before: Obstacle(0),
+                    after: Obstacle(1),
+                },
+            ]
+        );
+        assert_eq!(Obstacle(2).apply_all(&[]), (Obstacle(2), Vec::new()));
+    }
+
+    fn heist() -> CompoundObstacle {
+        CompoundObstacle::new(vec![
+            ("Wall".to_string(), Obstacle(1)),
+            ("Lock".to_string(), Obstacle(3)),
+            ("Guard".to_string(), Obstacle(2)),
+        ])
+    }
+
+    #[test]
+    fn sequences_need_one_result_per_stage() {
+        let heist = heist();
+        assert_eq!(
+            heist.evaluate_sequence(&[rolled(1), rolled(3)]),
+            Err(StageCountMismatch {
+                stages: 3,
+                results: 2
+            })
+        );
+        let four = [rolled(1), rolled(3), rolled(2), rolled(0)];
+        let error = heist.evaluate_sequence(&four).unwrap_err();
+        assert_eq!(
+            error,
+            StageCountMismatch {
+                stages: 3,
+                results: 4
+            }
+        );
+        assert_eq!(error.to_string(), "4 result(s) for 3 stage(s)");
+        assert_eq!(
+            CompoundObstacle::new(Vec::new()).evaluate_sequence(&[]),
+            Ok(CompoundOutcome::default())
+        );
+    }
+
+    #[test]
+    fn sequences_report_the_first_failure_and_total_margin() {
+        let outcome = heist()
+            .evaluate_sequence(&[rolled(3), rolled(1), rolled(1)])
+            .unwrap();
+        assert_eq!(outcome.first_failure, Some(1));
+        assert!(!outcome.is_success());
+        // +2, then -2, then -1.
+        assert_eq!(outcome.total_margin, -1);
+        assert_eq!(
+            outcome.stages[0],
+            StageOutcome {
+                label: "Wall".into(),
+                obstacle: Obstacle(1),
+                outcome: ObstacleOutcome::Exceeded { margin: 2 },
+            }
+        );
+
+        let outcome = heist()
+            .evaluate_sequence(&[rolled(1), rolled(3), rolled(4)])
+            .unwrap();
+        assert_eq!(outcome.first_failure, None);
+        assert!(outcome.is_success());
+        assert_eq!(outcome.total_margin, 2);
+    }
+
+    #[test]
+    fn rolling_every_stage_matches_evaluating_the_same_rolls() {
+        let stat = four_dice(Quality::Adept);
+        let outcome = heist().roll_all(
+            &stat,
+            0,
+            0,
+            &mut rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15),
+        );
+
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+        let results: Vec<RollResult> = (0..3)
+            .map(|_| roll_stat_with_rng(&stat, 0, 0, &mut rng))
+            .collect();
+        assert_eq!(heist().evaluate_sequence(&results), Ok(outcome.clone()));
+        let successes: Vec<isize> = results.iter().map(|result| result.successes).collect();
+        assert_eq!(successes, [3, 4, 4]);
+        assert_eq!(outcome.first_failure, None);
+        assert_eq!(outcome.total_margin, 5);
+    }
+
+    #[test]
+    fn ranges_swap_their_bounds() {
+        assert_eq!(ObstacleRange::new(6, 3), ObstacleRange { min: 3, max: 6 });
+        assert_eq!(ObstacleRange::new(3, 6), ObstacleRange { min: 3, max: 6 });
+        assert_eq!(ObstacleRange::new(4, 4), ObstacleRange { min: 4, max: 4 });
+    }
+
+    #[test]
+    fn ranges_have_a_midpoint() {
+        assert_eq!(ObstacleRange::new(3, 6).midpoint(), Obstacle(4));
+        assert_eq!(ObstacleRange::new(2, 6).midpoint(), Obstacle(4));
+        assert_eq!(ObstacleRange::new(5, 5).midpoint(), Obstacle(5));
+        assert_eq!(
+            ObstacleRange::new(usize::MAX - 1, usize::MAX).midpoint(),
+            Obstacle(usize::MAX - 1)
+        );
+        // The public fields may be out of order, but still make a range.
+        assert_eq!(ObstacleRange { min: 6, max: 2 }.midpoint(), Obstacle(4));
+    }
+
+    #[test]
+    fn samples_stay_in_the_range() {
+        let mut rng = rand::rngs::mock::StepRng::new(0, 0x9e37_79b9_7f4a_7c15);
+        let range = ObstacleRange::new(3, 6);
+        let mut seen = [false; 4];
+        for _ in 0..200 {
+            let Obstacle(level) = range.sample(&mut rng);
+            assert!((3..=6).contains(&level), "{level}");
+            seen[level - 3] = true;
+        }
+        assert_eq!(seen, [true; 4]);
+        for _ in 0..20 {
+            let Obstacle(level) = ObstacleRange { min: 6, max: 3 }.sample(&mut rng);
+            assert!((3..=6).contains(&level), "{level}");
+        }
+        assert_eq!(ObstacleRange::new(2, 2).sample(&mut rng), Obstacle(2));
+    }
+
+    #[test]
+    fn ranges_display_and_pad() {
+        let range = ObstacleRange::new(6, 3);
+        assert_eq!(range.to_string(), "Ob3–Ob6");
+        assert_eq!(format!("[{range:>9}]"), "[  Ob3–Ob6]");
+        assert_eq!(format!("[{range:<8}]"), "[Ob3–Ob6 ]");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn ranges_round_trip_through_json() {
+        let range = ObstacleRange::new(3, 6);
+        assert_eq!(
+            serde_json::to_string(&range).unwrap(),
+            r#"{"min":3,"max":6}"#
+        );
+        assert_json_roundtrip(&range);
+    }
+
+    #[cfg(feature = "bitcode")]
+    #[test]
+    fn ranges_round_trip_through_bitcode() {
+        assert_bitcode_roundtrip(&ObstacleRange::new(3, 6));
+    }
+
+    #[test]
+    fn streaks_extend_flip_and_reset() {
+        let mut tracker = StreakTracker::new();
+        assert_eq!(tracker.current_streak(), (Streak::Success, 0));
+
+        let check = |successes| Obstacle(2).check(rolled(successes));
+        for (successes, streak) in [
+            (2, (Streak::Success, 1)),
+            (3, (Streak::Success, 2)),
+            (1, (Streak::Failure, 1)),
+            (-1, (Streak::Failure, 2)),
+            (0, (Streak::Failure, 3)),
+            (2, (Streak::Success, 1)),
+        ] {
+            tracker.record(&check(successes));
+            assert_eq!(tracker.current_streak(), streak, "after {successes}");
+        }
+
+        tracker.reset();
+        assert_eq!(tracker, StreakTracker::new());
+        assert_eq!(tracker.current_streak(), (Streak::Success, 0));
+        // The success before the reset isn't extended.
+        tracker.record(&check(2));
+        assert_eq!(tracker.current_streak(), (Streak::Success, 1));
+    }
+
+    #[test]
+    fn successes_chip_away_at_extended_obstacles() {
+        let mut wall = Obstacle(5);
+        assert!(!wall.apply_successes(-3));
+        assert_eq!(wall, Obstacle(5));
+        assert!(!wall.apply_successes(isize::MIN));
+        assert_eq!(wall, Obstacle(5));
+        assert!(!wall.apply_successes(2));
+        assert_eq!(wall, Obstacle(3));
+        assert!(wall.apply_successes(10));
+        assert_eq!(wall, Obstacle(0));
+        // Once cleared it stays cleared, whatever is rolled.
+        assert!(wall.apply_successes(-1));
+        assert!(wall.apply_successes(0));
+
+        let mut huge = Obstacle(usize::MAX);
+        assert!(!huge.apply_successes(isize::MAX));
+        assert_eq!(huge, Obstacle(usize::MAX - isize::MAX.unsigned_abs()));
+        assert!(!huge.apply_successes(isize::MAX));
+        assert_eq!(huge, Obstacle(1));
+        assert!(huge.apply_successes(isize::MAX));
+        assert_eq!(huge, Obstacle(0));
+    }
+
+    #[test]
+    fn lenient_parsing_warns_about_unreasonable_obstacles() {
+        let (obstacle, warnings) = Obstacle::parse_lenient("Ob999").unwrap();
+        assert_eq!(obstacle, Obstacle(999));
+        assert_eq!(
+            warnings,
+            [ParseWarning::Unreasonable(ObstacleIssue::Unreasonable {
+                level: 999,
+                max: Obstacle::MAX_REASONABLE,
+            })]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "Ob999 is above the reasonable maximum of Ob20"
+        );
+
+        let (obstacle, warnings) = Obstacle::parse_lenient("Ob20").unwrap();
+        assert_eq!(obstacle, Obstacle(20));
+        assert!(warnings.is_empty());
+        assert!(Obstacle::parse_lenient("Obx").is_err());
+    }
+
+    #[test]
+    fn custom_limits_are_honored() {
+        let strict = ObstacleLimits { max_reasonable: 5 };
+        assert_eq!(Obstacle(5).validate_with(&strict), Ok(()));
+        assert_eq!(
+            Obstacle(6).validate_with(&strict),
+            Err(ObstacleIssue::Unreasonable { level: 6, max: 5 })
+        );
+        assert!(Obstacle(6).validate().is_ok());
+
+        let lax = ObstacleLimits {
+            max_reasonable: 1_000,
+        };
+        assert_eq!(Obstacle(999).validate_with(&lax), Ok(()));
+        assert!(Obstacle(999).validate().is_err());
     }
 }
@@ -0,0 +1,405 @@
+//! # Patch
+//! Small edits to a character, for syncing changes over the network
+//! without resending the whole sheet.
+//!
+//! A [`CharacterPatch`] replaces whole fields, so an edit to one
+//! quirk resends the quirk list, but nothing else. Each patch records
+//! a fingerprint of the character it was made from and only applies
+//! to that exact character, so patches applied out of order or to
+//! the wrong copy fail instead of silently mixing states.
+
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use bitcode::{Decode, Encode};
+
+use crate::{
+    serialization::envelope::crc32, Argos, Character, Inspiration, InspirationEvent, Item, Quirk,
+    Resource, RollMacro, Stat,
+};
+
+/// A replacement for one of a character's fields.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CharacterEdit {
+    Name(String),
+    Stock(String),
+    Stats(Vec<Stat>),
+    Skills(Vec<Stat>),
+    Resources(Vec<Resource>),
+    Quirks(Vec<Quirk>),
+    Inspiration(Inspiration),
+    Argos(Argos),
+    Inventory(Vec<Item>),
+    Macros(Vec<RollMacro>),
+    InspirationLog(Vec<InspirationEvent>),
+}
+
+/// An ordered list of edits turning one character into another.
+#[derive(Clone, PartialEq, Debug, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CharacterPatch {
+    /// The [`Character::fingerprint`] the patch applies to.
+    pub base: u32,
+    pub edits: Vec<CharacterEdit>,
+}
+
+impl CharacterPatch {
+    /// Whether the patch changes nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+}
+
+/// A patch couldn't be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchError {
+    /// The patch was made from a different character, or from this
+    /// one in a different state.
+    BaseMismatch { expected: u32, actual: u32 },
+}
+
+impl core::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PatchError::BaseMismatch { expected, actual } => write!(
+                f,
+                "patch is for character {expected:08x}, but this is {actual:08x}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for PatchError {}
+
+impl Character {
+    /// A checksum of the character's saved state, including its id
+    /// but not its scene-scoped `temp_effects`.
+    ///
+    /// Characters with the same fingerprint are almost certainly
+    /// identical. Fingerprints are only comparable between builds
+    /// with the same bitcode layout.
+    #[must_use]
+    pub fn fingerprint(&self) -> u32 {
+        crc32(&self.encode_saved())
+    }
+
+    /// The patch turning `base` into `updated`, with one edit for
+    /// each field that differs.
+    ///
+    /// Ids aren't patched: `updated` should be an edited copy of
+    /// `base`, sharing its id. A patch between characters with
+    /// different ids still applies, but keeps `base`'s id.
+    ///
+    /// Temp effects aren't patched either, as they aren't saved: the
+    /// patched character keeps its own.
+    #[must_use]
+    pub fn create_patch(base: &Character, updated: &Character) -> CharacterPatch {
+        let mut edits = Vec::new();
+        macro_rules! diff {
+            ($($field:ident => $edit:ident),* $(,)?) => {
+                $(
+                    if base.$field != updated.$field {
+                        edits.push(CharacterEdit::$edit(updated.$field.clone()));
+                    }
+                )*
+            };
+        }
+        diff!(
+            name => Name,
+            stock => Stock,
+            stats => Stats,
+            skills => Skills,
+            resources => Resources,
+            quirks => Quirks,
+            inspiration => Inspiration,
+            argos => Argos,
+            inventory => Inventory,
+            macros => Macros,
+            inspiration_log => InspirationLog,
+        );
+        CharacterPatch {
+            base: base.fingerprint(),
+            edits,
+        }
+    }
+
+    /// Applies a patch from [`Character::create_patch`], in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PatchError::BaseMismatch`], changing nothing, if the
+    /// patch wasn't made from this character in its current state.
+    pub fn apply_patch(&mut self, patch: CharacterPatch) -> Result<(), PatchError> {
+        let actual = self.fingerprint();
+        if actual != patch.base {
+            return Err(PatchError::BaseMismatch {
+                expected: patch.base,
+                actual,
+            });
+        }
+        for edit in patch.edits {
+            match edit {
+                CharacterEdit::Name(name) => self.name = name,
+                CharacterEdit::Stock(stock) => self.stock = stock,
+                CharacterEdit::Stats(stats) => self.stats = stats,
+                CharacterEdit::Skills(skills) => self.skills = skills,
+                CharacterEdit::Resources(resources) => self.resources = resources,
+                CharacterEdit::Quirks(quirks) => self.quirks = quirks,
+                CharacterEdit::Inspiration(inspiration) => self.inspiration = inspiration,
+                CharacterEdit::Argos(argos) => self.argos = argos,
+                CharacterEdit::Inventory(inventory) => self.inventory = inventory,
+                CharacterEdit::Macros(macros) => self.macros = macros,
+                CharacterEdit::InspirationLog(log) => self.inspiration_log = log,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use crate::{Quality, QuirkCategory, TempEffect};
+
+    fn base() -> Character {
+        let mut character = Character::new("Ash".into());
+        character.stat_mut("Will").unwrap().quantity = 4;
+        character
+    }
+
+    /// `base` with every patched field changed.
+    fn edited(base: &Character) -> Character {
+        let mut updated = base.clone();
+        updated.name = "Ash the Bold".into();
+        updated.stock = "Human".into();
+        updated.stats[0].quality = Quality::Adept;
+        updated.skills.push(Stat::new("Climbing".into()));
+        updated
+            .resources
+            .push(Resource::new(Stat::new("Wealth".into()), true));
+        updated.quirks.push(Quirk::new("Stubborn".into()));
+        updated.inspiration.ethos = 2;
+        updated.argos.add("Find the sea".into());
+        updated.inventory.push(Item::new("Rope".into()));
+        updated
+            .macros
+            .push(RollMacro::new("Climb".into(), "Climbing".into()));
+        updated.inspiration_log.push(InspirationEvent {
+            category: QuirkCategory::Ethos,
+            delta: 2,
+            reason: "Held firm.".into(),
+            session: "1".into(),
+        });
+        updated
+    }
+
+    #[test]
+    fn applying_a_patch_gives_the_updated_character() {
+        let base = base();
+        let updated = edited(&base);
+        let patch = Character::create_patch(&base, &updated);
+        assert_eq!(patch.edits.len(), 11);
+
+        let mut patched = base.clone();
+        patched.apply_patch(patch).unwrap();
+        assert_eq!(patched, updated);
+    }
+
+    #[test]
+    fn patches_only_carry_changed_fields() {
+        let base = base();
+        let mut updated = base.clone();
+        updated.inventory.push(Item::new("Rope".into()));
+        let patch = Character::create_patch(&base, &updated);
+        assert_eq!(
+            patch.edits,
+            [CharacterEdit::Inventory(updated.inventory.clone())]
+        );
+
+        let mut patched = base.clone();
+        patched.apply_patch(patch).unwrap();
+        assert_eq!(patched, updated);
+    }
+
+    #[test]
+    fn patches_between_equal_characters_are_empty() {
+        let base = base();
+        let patch = Character::create_patch(&base, &base.clone());
+        assert!(patch.is_empty());
+
+        let mut patched = base.clone();
+        patched.apply_patch(patch).unwrap();
+        assert_eq!(patched, base);
+    }
+
+    #[test]
+    fn temp_effects_are_not_patched() {
+        let base = base();
+        let mut updated = base.clone();
+        updated
+            .temp_effects
+            .push(TempEffect::new("Speed".into(), -1, 0, 2));
+        assert_eq!(updated.fingerprint(), base.fingerprint());
+        assert!(Character::create_patch(&base, &updated).is_empty());
+
+        let mut patched = updated.clone();
+        patched
+            .apply_patch(Character::create_patch(&base, &edited(&base)))
+            .unwrap();
+        assert_eq!(patched.temp_effects, updated.temp_effects);
+    }
+
+    #[test]
+    fn patches_only_apply_to_their_base() {
+        let base = base();
+        let patch = Character::create_patch(&base, &edited(&base));
+
+        let mut other = base.clone();
+        other.stock = "Elf".into();
+        let error = other.apply_patch(patch.clone()).unwrap_err();
+        assert_eq!(
+            error,
+            PatchError::BaseMismatch {
+                expected: base.fingerprint(),
+                actual: other.fingerprint(),
+            }
+        );
+        assert_eq!(other.stock, "Elf");
+
+        let mut patched = base.clone();
+        patched.apply_patch(patch.clone()).unwrap();
+        assert!(patched.apply_patch(patch).is_err());
+    }
+
+    #[test]
+    fn ids_are_not_patched() {
+        let base = base();
+        let stranger = Character::new("Bo".into());
+        assert_ne!(stranger.id(), base.id());
+
+        let mut patched = base.clone();
+        patched
+            .apply_patch(Character::create_patch(&base, &stranger))
+            .unwrap();
+        assert_eq!(patched.id(), base.id());
+        assert_eq!(
+            patched,
+            Character {
+                id: base.id(),
+                ..stranger
+            }
+        );
+    }
+
+    #[test]
+    fn patches_round_trip() {
+        let base = base();
+        let patch = Character::create_patch(&base, &edited(&base));
+        crate::test_support::assert_bitcode_roundtrip(&patch);
+        #[cfg(feature = "serde")]
+        crate::test_support::assert_json_roundtrip(&patch);
+    }
+
+    fn word(rng: &mut StdRng) -> String {
+        ["Ash", "Bo", "Rope", "Will", "Sea", ""][rng.gen_range(0..6)].to_string()
+    }
+
+    fn stats(rng: &mut StdRng) -> Vec<Stat> {
+        (0..rng.gen_range(0..4))
+            .map(|_| Stat {
+                quality: [Quality::Basic, Quality::Adept, Quality::Superb][rng.gen_range(0..3)],
+                quantity: rng.gen_range(0..7),
+                checks: rng.gen_bool(0.5).then(|| rng.gen_range(0..5)),
+                ..Stat::new(word(rng))
+            })
+            .collect()
+    }
+
+    /// Replaces each patched field of `character` with a random value,
+    /// with even odds, so pairs differ in a random subset of fields.
+    fn scramble(character: &mut Character, rng: &mut StdRng) {
+        if rng.gen_bool(0.5) {
+            character.name = word(rng);
+        }
+        if rng.gen_bool(0.5) {
+            character.stock = word(rng);
+        }
+        if rng.gen_bool(0.5) {
+            character.stats = stats(rng);
+        }
+        if rng.gen_bool(0.5) {
+            character.skills = stats(rng);
+        }
+        if rng.gen_bool(0.5) {
+            character.resources = stats(rng)
+                .into_iter()
+                .map(|stat| Resource::new(stat, rng.gen_bool(0.5)))
+                .collect();
+        }
+        if rng.gen_bool(0.5) {
+            character.quirks = (0..rng.gen_range(0..3))
+                .map(|_| Quirk {
+                    boons: vec![word(rng)],
+                    ..Quirk::new(word(rng))
+                })
+                .collect();
+        }
+        if rng.gen_bool(0.5) {
+            character.inspiration = Inspiration {
+                ethos: rng.gen_range(0..3),
+                pathos: rng.gen_range(0..3),
+                logos: rng.gen_range(0..3),
+            };
+        }
+        if rng.gen_bool(0.5) {
+            character.argos = Argos((0..rng.gen_range(0..3)).map(|_| word(rng)).collect());
+        }
+        if rng.gen_bool(0.5) {
+            character.inventory = (0..rng.gen_range(0..3))
+                .map(|_| Item {
+                    quantity: rng.gen_range(0..4),
+                    ..Item::new(word(rng))
+                })
+                .collect();
+        }
+        if rng.gen_bool(0.5) {
+            character.macros = (0..rng.gen_range(0..2))
+                .map(|_| RollMacro::new(word(rng), word(rng)))
+                .collect();
+        }
+        if rng.gen_bool(0.5) {
+            character.inspiration_log = (0..rng.gen_range(0..2))
+                .map(|_| InspirationEvent {
+                    category: QuirkCategory::Ethos,
+                    delta: rng.gen_range(-2..3),
+                    reason: word(rng),
+                    session: word(rng),
+                })
+                .collect();
+        }
+    }
+
+    #[test]
+    fn patches_reproduce_generated_updates() {
+        for seed in 0..200 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut base = base();
+            scramble(&mut base, &mut rng);
+            let mut updated = base.clone();
+            scramble(&mut updated, &mut rng);
+
+            let patch = Character::create_patch(&base, &updated);
+            assert_eq!(patch.is_empty(), base == updated, "seed {seed}");
+            let mut patched = base.clone();
+            patched.apply_patch(patch).unwrap();
+            assert_eq!(patched, updated, "seed {seed}");
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! # Probability
+//! Exact success odds for Arrata-style rolls.
+//!
+//! Odds follow the same rules as [`crate::roll_stat`], including
+//! exploding 6s with advantage and subtracted 1s with disadvantage.
+//! Explosion chains are followed [`MAX_EXPLOSIONS`] deep, past which
+//! the remaining probability is too small to matter.
+//!
+//! Pools of more than [`crate::MAX_POOL`] dice are clamped to that
+//! many, just as they are by [`crate::roll_stat`], so odds come back
+//! in bounded time however absurd the inputs.
+
+use alloc::{vec, vec::Vec};
+
+#[cfg(feature = "std")]
+use alloc::collections::VecDeque;
+use core::cmp::Ordering;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::{dice::pool_size, Obstacle, Quality, Stat, MAX_POOL};
+
+/// How many times in a row a single die's explosions are followed.
+pub const MAX_EXPLOSIONS: usize = 24;
+
+/// Probabilities below this are dropped from the tail of a
+/// distribution as it's built.
+const NEGLIGIBLE: f64 = 1e-18;
+
+/// The probability of every possible number of successes on a roll.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuccessDistribution {
+    /// The number of successes at index 0. Negative with disadvantage.
+    min: isize,
+    probabilities: Vec<f64>,
+}
+
+impl SuccessDistribution {
+    /// The probability of exactly `successes`.
+    #[must_use]
+    pub fn probability(&self, successes: isize) -> f64 {
+        successes
+            .checked_sub(self.min)
+            .and_then(|index| usize::try_from(index).ok())
+            .and_then(|index| self.probabilities.get(index))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The probability of at least `successes`.
+    #[must_use]
+    pub fn at_least(&self, successes: isize) -> f64 {
+        if successes <= self.min {
+            return 1.0;
+        }
+        let start = successes
+            .checked_sub(self.min)
+            .and_then(|start| usize::try_from(start).ok())
+            .unwrap_or(usize::MAX);
+        match self.probabilities.get(start..) {
+            Some(tail) if !tail.is_empty() => tail.iter().sum::<f64>().min(1.0),
+            _ => 0.0,
+        }
+    }
+
+    /// The average number of successes.
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(index, p)| {
+                let successes = self.min + isize::try_from(index).unwrap_or(isize::MAX);
+                f64::from(i32::try_from(successes).unwrap_or(i32::MAX)) * p
+            })
+            .sum()
+    }
+
+    /// The fewest successes with a nonzero probability.
+    #[must_use]
+    pub fn min(&self) -> isize {
+        self.min
+    }
+
+    /// The most successes with a nonnegligible probability.
+    #[must_use]
+    pub fn max(&self) -> isize {
+        self.min + isize::try_from(self.probabilities.len()).unwrap_or(isize::MAX) - 1
+    }
+}
+
+/// Computes the distribution of successes for rolling a stat with
+/// advantage and disadvantage.
+#[must_use]
+pub fn success_distribution(
+    stat: &Stat,
+    advantage: usize,
+    disadvantage: usize,
+) -> SuccessDistribution {
+    let Some(quantity) = pool_size(stat.quantity, advantage, disadvantage).map(|q| q.min(MAX_POOL))
+    else {
+        return SuccessDistribution {
+            min: 0,
+            probabilities: vec![1.0],
+        };
+    };
+
+    let die = die_distribution(stat.quality.threshold(), advantage > 0, disadvantage > 0);
+    let mut total = SuccessDistribution {
+        min: 0,
+        probabilities: vec![1.0],
+    };
+    for _ in 0..quantity {
+        total = convolve(&total, &die);
+    }
+    total
+}
+
+/// The probability that rolling a stat with advantage and
+/// disadvantage meets the obstacle.
+#[must_use]
+pub fn success_probability(
+    stat: &Stat,
+    advantage: usize,
+    disadvantage: usize,
+    obstacle: &Obstacle,
+) -> f64 {
+    let Ok(level) = isize::try_from(obstacle.0) else {
+        return 0.0;
+    };
+    success_distribution(stat, advantage, disadvantage).at_least(level)
+}
+
+/// Compares the odds of two rolls meeting the same obstacle, each
+/// given as a stat, advantage, and disadvantage.
+///
+/// Returns [`Ordering::Greater`] if `a` is more likely to succeed,
+/// [`Ordering::Less`] if `b` is, and [`Ordering::Equal`] if they're
+/// the same, so `compare_configs(a, b, ob).is_gt()` reads as "a is
+/// the better bet".
+#[must_use]
+pub fn compare_configs(
+    a: (&Stat, usize, usize),
+    b: (&Stat, usize, usize),
+    obstacle: &Obstacle,
+) -> Ordering {
+    let odds = |(stat, advantage, disadvantage): (&Stat, usize, usize)| {
+        success_probability(stat, advantage, disadvantage, obstacle)
+    };
+    odds(a).total_cmp(&odds(b))
+}
+
+/// The average number of successes from rolling a stat with
+/// advantage and disadvantage.
+#[must_use]
+pub fn expected_successes(stat: &Stat, advantage: usize, disadvantage: usize) -> f64 {
+    success_distribution(stat, advantage, disadvantage).mean()
+}
+
+/// Resolves a check without rolling by taking the average result,
+/// for fast-forwarding trivial scenes.
+///
+/// The expected successes are rounded to the nearest whole number,
+/// halves rounding up, and succeed if they meet the obstacle.
+#[must_use]
+pub fn resolve_average(
+    stat: &Stat,
+    advantage: usize,
+    disadvantage: usize,
+    obstacle: &Obstacle,
+) -> bool {
+    let level = f64::from(u32::try_from(obstacle.0).unwrap_or(u32::MAX));
+    expected_successes(stat, advantage, disadvantage) >= level - 0.5
+}
+
+/// The probability of meeting each obstacle from `Ob0` up, for
+/// rolling a stat with advantage and disadvantage.
+///
+/// Index `n` holds the odds against `Ob{n}`. The table stops at the
+/// most successes the roll can reasonably produce.
+#[must_use]
+pub fn odds_table(stat: &Stat, advantage: usize, disadvantage: usize) -> Vec<f64> {
+    let distribution = success_distribution(stat, advantage, disadvantage);
+    (0..=distribution.max().max(0))
+        .map(|level| distribution.at_least(level))
+        .collect()
+}
+
+/// The hardest obstacle with at least an even chance of success
+/// for rolling a stat with advantage and disadvantage.
+///
+/// Returns `0` if even `Ob0` is worse than a coin flip, which can
+/// happen with heavy disadvantage.
+#[must_use]
+pub fn break_even_obstacle(stat: &Stat, advantage: usize, disadvantage: usize) -> usize {
+    odds_table(stat, advantage, disadvantage)
+        .iter()
+        .rposition(|&p| p >= 0.5)
+        .unwrap_or(0)
+}
+
+/// The distribution of successes from a single die, including
+/// any explosions.
+fn die_distribution(threshold: u8, advantage: bool, disadvantage: bool) -> SuccessDistribution {
+    let value = |face: u8| -> isize {
+        isize::from(face >= threshold) - isize::from(disadvantage && face == 1)
+    };
+
+    // Build up from a die that can't explode to one that can
+    // explode `MAX_EXPLOSIONS` times.
+    let depth = if advantage { MAX_EXPLOSIONS } else { 0 };
+    let mut chain = SuccessDistribution {
+        min: 0,
+        probabilities: vec![1.0],
+    };
+    for level in 0..=depth {
+        let mut next = SuccessDistribution {
+            min: -1,
+            probabilities: vec![0.0; chain.probabilities.len() + 2],
+        };
+        for face in 1..=6 {
+            let explodes = level > 0 && face == 6;
+            if explodes {
+                for (index, p) in chain.probabilities.iter().enumerate() {
+                    let successes = value(face) + chain.min + isize::try_from(index).unwrap_or(0);
+                    next.add(successes, p / 6.0);
+                }
+            } else {
+                next.add(value(face), 1.0 / 6.0);
+            }
+        }
+        chain = next.trimmed();
+    }
+    chain
+}
+
+impl SuccessDistribution {
+    fn add(&mut self, successes: isize, p: f64) {
+        if let Some(slot) = usize::try_from(successes - self.min)
+            .ok()
+            .and_then(|index| self.probabilities.get_mut(index))
+        {
+            *slot += p;
+        }
+    }
+
+    /// Drops negligible probabilities from both ends.
+    fn trimmed(mut self) -> Self {
+        while self.probabilities.len() > 1 && self.probabilities.last() < Some(&NEGLIGIBLE) {
+            self.probabilities.pop();
+        }
+        let leading = self
+            .probabilities
+            .iter()
+            .take(self.probabilities.len() - 1)
+            .take_while(|p| **p < NEGLIGIBLE)
+            .count();
+        self.probabilities.drain(..leading);
+        self.min += isize::try_from(leading).unwrap_or(0);
+        self
+    }
+}
+
+fn convolve(a: &SuccessDistribution, b: &SuccessDistribution) -> SuccessDistribution {
+    let mut out = SuccessDistribution {
+        min: a.min + b.min,
+        probabilities: vec![0.0; a.probabilities.len() + b.probabilities.len() - 1],
+    };
+    for (i, pa) in a.probabilities.iter().enumerate() {
+        for (j, pb) in b.probabilities.iter().enumerate() {
+            out.probabilities[i + j] += pa * pb;
+        }
+    }
+    out.trimmed()
+}
+
+/// The inputs a cached probability was computed from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProbabilityKey {
+    pub quality: Quality,
+    pub quantity: usize,
+    pub advantage: usize,
+    pub disadvantage: usize,
+    pub obstacle: Obstacle,
+}
+
+/// A bounded cache of success probabilities for interactive tools
+/// that ask for the same odds repeatedly.
+///
+/// Once full, the oldest entry is evicted to make room.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct ProbabilityCache {
+    capacity: usize,
+    entries: HashMap<ProbabilityKey, f64>,
+    order: VecDeque<ProbabilityKey>,
+}
+
+#[cfg(feature = "std")]
+impl ProbabilityCache {
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// The probability that rolling a stat with advantage and
+    /// disadvantage meets the obstacle, computed on first use.
+    ///
+    /// See [`success_probability`].
+    pub fn probability(
+        &mut self,
+        stat: &Stat,
+        advantage: usize,
+        disadvantage: usize,
+        obstacle: &Obstacle,
+    ) -> f64 {
+        let key = ProbabilityKey {
+            quality: stat.quality,
+            quantity: stat.quantity,
+            advantage,
+            disadvantage,
+            obstacle: *obstacle,
+        };
+        if let Some(p) = self.entries.get(&key) {
+            return *p;
+        }
+
+        let p = success_probability(stat, advantage, disadvantage, obstacle);
+        if self.capacity > 0 {
+            if self.entries.len() >= self.capacity {
+                self.evict_oldest();
+            }
+            self.order.push_back(key.clone());
+            self.entries.insert(key, p);
+        }
+        p
+    }
+
+    /// A previously computed probability, if cached.
+    #[must_use]
+    pub fn get(&self, key: &ProbabilityKey) -> Option<f64> {
+        self.entries.get(key).copied()
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Changes the capacity, evicting the oldest entries if
+    /// the cache is now over capacity.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.evict_oldest();
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some(oldest) = self.order.pop_front() {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for ProbabilityCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(quantity: usize) -> Stat {
+        Stat {
+            quantity,
+            ..Stat::new("Will".into())
+        }
+    }
+
+    // These short-circuit, so the odds are exact.
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn absurd_obstacles_are_impossible() {
+        for level in [999, usize::MAX] {
+            assert_eq!(success_probability(&stat(4), 2, 0, &Obstacle(level)), 0.0);
+        }
+        assert_eq!(success_probability(&stat(4), 0, 0, &Obstacle(5)), 0.0);
+    }
+
+    #[test]
+    fn absurd_pools_are_clamped() {
+        let largest = success_distribution(&stat(MAX_POOL), 0, 0);
+        for quantity in [MAX_POOL + 1, usize::MAX] {
+            assert_eq!(success_distribution(&stat(quantity), 0, 0), largest);
+        }
+        let clamped = success_distribution(&stat(4), usize::MAX, 0);
+        assert_eq!(clamped, success_distribution(&stat(MAX_POOL), 1, 0));
+    }
+
+    #[test]
+    fn the_largest_pool_still_has_odds() {
+        let distribution = success_distribution(&stat(MAX_POOL), 0, 0);
+        assert!((distribution.mean() - 500.0).abs() < 1e-6);
+        assert!(success_probability(&stat(MAX_POOL), 0, 0, &Obstacle(500)) > 0.5);
+    }
+}
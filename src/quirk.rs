@@ -1,17 +1,50 @@
-use serde::{Deserialize, Serialize};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
+use core::cmp::Ordering;
+use core::ops::{Add, AddAssign, Sub, SubAssign};
+use core::str::FromStr;
+
+use rand::{seq::SliceRandom, Rng};
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Serialize};
+
+#[cfg(feature = "bitcode")]
 use bitcode::{Decode, Encode};
 
+use crate::{markdown, Quality};
+
 /// A struct for Quirks. Boons
 /// and flaws are optional as some
 /// Quirks are purely cosmetic/neutral.
-#[derive(Encode, Decode, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Quirk {
     pub name: String,
+    /// Files from the pre-rewrite Arrata app store this as `type`.
+    #[cfg_attr(feature = "serde", serde(alias = "type"))]
     pub category: QuirkCategory,
     pub description: String,
     pub boons: Vec<String>,
     pub flaws: Vec<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub prerequisites: Vec<Prerequisite>,
+    /// Free-form labels for organizing homebrew, such as
+    /// a source book or campaign arc.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub tags: Vec<String>,
+    /// How often the quirk drove play this session.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub uses_this_session: usize,
+    /// Notes on this session's uses, for the end-of-session
+    /// inspiration discussion.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub session_notes: Vec<String>,
 }
 
 impl Quirk {
@@ -23,18 +56,938 @@ impl Quirk {
             category: QuirkCategory::Ethos,
             boons: vec![],
             flaws: vec![],
+            prerequisites: vec![],
+            tags: vec![],
+            uses_this_session: 0,
+            session_notes: vec![],
+        }
+    }
+
+    /// Compares quirks for display on a sheet.
+    ///
+    /// Quirks are ordered by category (Ethos, Pathos, Logos,
+    /// then Uncategorized), then by name ignoring case.
+    #[must_use]
+    pub fn cmp_for_display(&self, other: &Self) -> Ordering {
+        self.category
+            .cmp(&other.category)
+            .then_with(|| self.name.to_lowercase().cmp(&other.name.to_lowercase()))
+    }
+
+    /// Parses a quirk from a block of text, as found in GM prep notes.
+    ///
+    /// The first line holds the name, either bolded (`**Coward**`) or
+    /// plain, optionally followed by a parenthesized category and a colon.
+    /// Everything after that is split on `Boon:` and `Flaw:` markers,
+    /// which may appear inline or on their own lines. Text outside of
+    /// any marker, including unrecognized trailing lines, is folded into
+    /// the description.
+    ///
+    /// ```text
+    /// **Coward** (Pathos): Runs from danger.
+    /// Boon: Hard to ambush.
+    /// Flaw: Disadvantage on Will vs fear.
+    /// ```
+    ///
+    /// Quirks without a category are [`QuirkCategory::Uncategorized`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`QuirkParseError`] if the block is empty, has no name,
+    /// has an unclosed `**` or `(`, or names an unknown category.
+    pub fn from_text_block(text: &str) -> Result<Quirk, QuirkParseError> {
+        let mut lines = text
+            .lines()
+            .map(|line| strip_bullet(line.trim()))
+            .filter(|line| !line.is_empty());
+
+        let header = lines.next().ok_or(QuirkParseError::Empty)?;
+
+        let (name, rest) = if let Some(bolded) = header.strip_prefix("**") {
+            let end = bolded.find("**").ok_or(QuirkParseError::UnclosedBold)?;
+            (&bolded[..end], &bolded[end + 2..])
+        } else {
+            let end = header.find(['(', ':']).unwrap_or(header.len());
+            (&header[..end], &header[end..])
+        };
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(QuirkParseError::MissingName);
+        }
+
+        let mut rest = rest.trim_start();
+        let mut category = QuirkCategory::Uncategorized;
+        if let Some(inner) = rest.strip_prefix('(') {
+            let end = inner.find(')').ok_or(QuirkParseError::UnclosedCategory)?;
+            category = inner[..end].parse()?;
+            rest = &inner[end + 1..];
+        }
+        let rest = rest.trim_start().trim_start_matches(':').trim();
+
+        let mut quirk = Quirk::new(name.to_string());
+        quirk.category = category;
+
+        let mut description = Vec::new();
+        for line in core::iter::once(rest).chain(lines) {
+            let (text, entries) = split_markers(line);
+            if !text.is_empty() {
+                description.push(text);
+            }
+            for (marker, entry) in entries {
+                let entry = entry.trim().trim_end_matches('.').trim_end();
+                if entry.is_empty() {
+                    continue;
+                }
+                match marker {
+                    Marker::Boon => quirk.boons.push(entry.to_string()),
+                    Marker::Flaw => quirk.flaws.push(entry.to_string()),
+                }
+            }
+        }
+        quirk.description = description.join(" ");
+
+        Ok(quirk)
+    }
+
+    /// Parses several quirks separated by blank lines.
+    ///
+    /// See [`Quirk::from_text_block`] for the format of each block.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`QuirkParseError`] encountered.
+    pub fn parse_many(text: &str) -> Result<Vec<Quirk>, QuirkParseError> {
+        let mut quirks = Vec::new();
+        let mut block = String::new();
+
+        for line in text.lines().chain(core::iter::once("")) {
+            if line.trim().is_empty() {
+                if !block.is_empty() {
+                    quirks.push(Quirk::from_text_block(&block)?);
+                    block.clear();
+                }
+            } else {
+                block.push_str(line);
+                block.push('\n');
+            }
+        }
+
+        Ok(quirks)
+    }
+
+    /// The passive stat modifiers among this quirk's boons and flaws.
+    ///
+    /// See [`PassiveModifier`] for what counts as one.
+    #[must_use]
+    pub fn passive_modifiers(&self) -> Vec<PassiveModifier> {
+        self.boons
+            .iter()
+            .chain(&self.flaws)
+            .filter_map(|effect| PassiveModifier::parse(effect))
+            .collect()
+    }
+
+    /// Summarizes how this quirk's boons weigh against its flaws.
+    ///
+    /// See [`EffectKind`] for how each boon and flaw is weighted.
+    /// The result is advisory only.
+    #[must_use]
+    pub fn balance(&self) -> QuirkBalance {
+        let weigh = |effects: &[String]| -> usize {
+            effects
+                .iter()
+                .map(|effect| EffectKind::classify(effect).weight())
+                .sum()
+        };
+        QuirkBalance {
+            name: self.name.clone(),
+            boons: self.boons.len(),
+            flaws: self.flaws.len(),
+            boon_weight: weigh(&self.boons),
+            flaw_weight: weigh(&self.flaws),
+        }
+    }
+
+    /// Merges another copy of this quirk into this one.
+    ///
+    /// Boons, flaws, prerequisites, and tags are unioned, dropping
+    /// duplicates. If the descriptions differ, the other description
+    /// is appended after a blank line (`"\n\n"`). Uses this session
+    /// are summed and session notes appended.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`QuirkMergeError`], leaving this quirk untouched, if
+    /// the names differ (ignoring case) or the categories differ.
+    pub fn merge(&mut self, other: Quirk) -> Result<(), QuirkMergeError> {
+        if !self.name.eq_ignore_ascii_case(&other.name) {
+            return Err(QuirkMergeError::NameMismatch(self.name.clone(), other.name));
+        }
+        if self.category != other.category {
+            return Err(QuirkMergeError::CategoryMismatch(
+                self.category.clone(),
+                other.category,
+            ));
+        }
+
+        if self.description.is_empty() {
+            self.description = other.description;
+        } else if !other.description.is_empty() && self.description != other.description {
+            self.description.push_str("\n\n");
+            self.description.push_str(&other.description);
+        }
+
+        for boon in other.boons {
+            if !self.boons.contains(&boon) {
+                self.boons.push(boon);
+            }
+        }
+        for flaw in other.flaws {
+            if !self.flaws.contains(&flaw) {
+                self.flaws.push(flaw);
+            }
+        }
+        for prerequisite in other.prerequisites {
+            if !self.prerequisites.contains(&prerequisite) {
+                self.prerequisites.push(prerequisite);
+            }
+        }
+        for tag in other.tags {
+            self.add_tag(tag);
+        }
+        self.uses_this_session = self
+            .uses_this_session
+            .saturating_add(other.uses_this_session);
+        self.session_notes.extend(other.session_notes);
+
+        Ok(())
+    }
+
+    /// What changed going from this quirk to `other`, such as
+    /// after a GM revises a homebrew quirk.
+    #[must_use]
+    pub fn diff(&self, other: &Quirk) -> QuirkDiff {
+        let missing_from = |from: &[String], to: &[String]| -> Vec<String> {
+            to.iter()
+                .filter(|entry| !from.contains(entry))
+                .cloned()
+                .collect()
+        };
+
+        QuirkDiff {
+            name: other.name.clone(),
+            category: (self.category != other.category)
+                .then(|| (self.category.clone(), other.category.clone())),
+            description_changed: self.description != other.description,
+            added_boons: missing_from(&self.boons, &other.boons),
+            removed_boons: missing_from(&other.boons, &self.boons),
+            added_flaws: missing_from(&self.flaws, &other.flaws),
+            removed_flaws: missing_from(&other.flaws, &self.flaws),
+        }
+    }
+
+    /// Whether the quirk has a tag, ignoring case.
+    #[must_use]
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Adds a tag unless the quirk already has it, ignoring case.
+    ///
+    /// Returns whether the tag was added.
+    pub fn add_tag(&mut self, tag: String) -> bool {
+        if self.has_tag(&tag) {
+            return false;
         }
+        self.tags.push(tag);
+        true
+    }
+
+    /// Removes a tag, ignoring case.
+    ///
+    /// Returns whether the tag was present.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let len = self.tags.len();
+        self.tags.retain(|t| !t.eq_ignore_ascii_case(tag));
+        self.tags.len() != len
+    }
+
+    /// The anchor for this quirk in markdown exports.
+    ///
+    /// ASCII letters and digits in the name are lowercased and kept,
+    /// each run of whitespace, `-`, or `_` becomes a single `-`, and
+    /// everything else is dropped, so `Iron Grip (Lesser)` becomes
+    /// `iron-grip-lesser`. A name with nothing left becomes `quirk`.
+    #[must_use]
+    pub fn anchor(&self) -> String {
+        markdown::slugify(&self.name, "quirk")
+    }
+
+    /// Formats the quirk as markdown for wikis.
+    ///
+    /// Produces an anchored heading at `heading_level` (clamped to
+    /// 1 through 6), the category as a badge line, the description,
+    /// and bulleted boons and flaws. See [`Quirk::anchor`] for how
+    /// anchors are derived.
+    #[must_use]
+    pub fn to_markdown(&self, heading_level: u8) -> String {
+        self.to_markdown_anchored(heading_level, &self.anchor())
+    }
+
+    /// [`Quirk::to_markdown`] with a given anchor, for documents
+    /// that need to keep anchors unique.
+    pub(crate) fn to_markdown_anchored(&self, heading_level: u8, anchor: &str) -> String {
+        let mut out = format!(
+            "<a id=\"{}\"></a>\n{} {}\n\n`{}`\n",
+            anchor,
+            markdown::heading(heading_level),
+            markdown::escape(&self.name),
+            self.category
+        );
+        if !self.description.is_empty() {
+            out.push('\n');
+            out.push_str(&markdown::escape(&self.description));
+            out.push('\n');
+        }
+        for (label, effects) in [("Boons", &self.boons), ("Flaws", &self.flaws)] {
+            if effects.is_empty() {
+                continue;
+            }
+            out.push_str("\n**");
+            out.push_str(label);
+            out.push_str("**\n\n");
+            for effect in effects {
+                out.push_str("- ");
+                out.push_str(&markdown::escape(effect));
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Removes duplicate tags, ignoring case and keeping
+    /// the first occurrence.
+    pub fn dedup_tags(&mut self) {
+        let mut seen: Vec<String> = Vec::with_capacity(self.tags.len());
+        self.tags.retain(|tag| {
+            let lower = tag.to_lowercase();
+            if seen.contains(&lower) {
+                false
+            } else {
+                seen.push(lower);
+                true
+            }
+        });
     }
 }
 
+#[derive(Clone, Copy)]
+enum Marker {
+    Boon,
+    Flaw,
+}
+
+fn strip_bullet(line: &str) -> &str {
+    ["- ", "* ", "• "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .unwrap_or(line)
+        .trim_start()
+}
+
+/// Splits a line into its leading text and any `Boon:`/`Flaw:` entries.
+fn split_markers(line: &str) -> (&str, Vec<(Marker, &str)>) {
+    const MARKERS: [(&str, Marker); 4] = [
+        ("boons:", Marker::Boon),
+        ("boon:", Marker::Boon),
+        ("flaws:", Marker::Flaw),
+        ("flaw:", Marker::Flaw),
+    ];
+
+    let lower = line.to_ascii_lowercase();
+    let mut found: Vec<(usize, usize, Marker)> = Vec::new();
+    let mut index = 0;
+    while index < lower.len() {
+        let at_word_start = index == 0
+            || lower[..index]
+                .chars()
+                .next_back()
+                .is_some_and(|c| !c.is_alphanumeric());
+        let marker = MARKERS
+            .iter()
+            .find(|(text, _)| at_word_start && lower[index..].starts_with(text));
+        if let Some((text, marker)) = marker {
+            found.push((index, index + text.len(), *marker));
+            index += text.len();
+        } else {
+            index += lower[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+
+    let text_end = found.first().map_or(line.len(), |(start, _, _)| *start);
+    let entries = found
+        .iter()
+        .enumerate()
+        .map(|(i, (_, end, marker))| {
+            let next = found.get(i + 1).map_or(line.len(), |(start, _, _)| *start);
+            (*marker, &line[*end..next])
+        })
+        .collect();
+
+    (line[..text_end].trim(), entries)
+}
+
 impl Default for Quirk {
     fn default() -> Self {
         Self::new("New Quirk!".into())
     }
 }
 
+/// A collection of quirks to pick from, such as a homebrew
+/// supplement.
+///
+/// In JSON, each entry is a quirk with an optional `weight`
+/// (default 1) controlling how often [`QuirkLibrary::random`]
+/// picks it.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuirkLibrary {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub quirks: Vec<LibraryEntry>,
+}
+
+/// A quirk in a [`QuirkLibrary`], with its weight for random picks.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LibraryEntry {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub quirk: Quirk,
+    /// A weight of zero is never picked at random.
+    #[cfg_attr(feature = "serde", serde(default = "default_weight"))]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl From<Quirk> for LibraryEntry {
+    fn from(quirk: Quirk) -> Self {
+        Self {
+            quirk,
+            weight: default_weight(),
+        }
+    }
+}
+
+/// Constraints on [`QuirkLibrary::random_set`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct RandomSetConstraints<'a> {
+    /// Only pick quirks in this category.
+    pub category: Option<&'a QuirkCategory>,
+    /// Pick at most one quirk per category.
+    pub one_per_category: bool,
+}
+
+impl QuirkLibrary {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a library from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON is not a valid library.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Adds a quirk with the default weight.
+    pub fn push(&mut self, quirk: Quirk) {
+        self.quirks.push(quirk.into());
+    }
+
+    /// Finds quirks whose name or any tag contains `query`,
+    /// ignoring case.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<&Quirk> {
+        let query = query.to_lowercase();
+        self.iter()
+            .filter(|quirk| {
+                quirk.name.to_lowercase().contains(&query)
+                    || quirk
+                        .tags
+                        .iter()
+                        .any(|tag| tag.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    /// Finds quirks with a tag, ignoring case.
+    #[must_use]
+    pub fn with_tag(&self, tag: &str) -> Vec<&Quirk> {
+        self.iter().filter(|quirk| quirk.has_tag(tag)).collect()
+    }
+
+    /// Every quirk in the library.
+    pub fn iter(&self) -> impl Iterator<Item = &Quirk> {
+        self.quirks.iter().map(|entry| &entry.quirk)
+    }
+
+    /// Picks a quirk at random by weight, optionally only from
+    /// one category.
+    ///
+    /// Returns `None` if there's nothing with a nonzero weight to pick.
+    pub fn random(&self, rng: &mut impl Rng, filter: Option<&QuirkCategory>) -> Option<Quirk> {
+        let candidates: Vec<&LibraryEntry> = self
+            .quirks
+            .iter()
+            .filter(|entry| filter.is_none_or(|category| &entry.quirk.category == category))
+            .collect();
+        candidates
+            .choose_weighted(rng, |entry| entry.weight)
+            .ok()
+            .map(|entry| entry.quirk.clone())
+    }
+
+    /// Picks up to `count` quirks at random by weight.
+    ///
+    /// No two picked quirks share a name, ignoring case. Fewer than
+    /// `count` quirks are returned if the constraints run out of
+    /// candidates.
+    pub fn random_set(
+        &self,
+        rng: &mut impl Rng,
+        count: usize,
+        constraints: RandomSetConstraints,
+    ) -> Vec<Quirk> {
+        let mut picked: Vec<Quirk> = Vec::with_capacity(count);
+        while picked.len() < count {
+            let candidates: Vec<&LibraryEntry> = self
+                .quirks
+                .iter()
+                .filter(|entry| {
+                    let quirk = &entry.quirk;
+                    constraints
+                        .category
+                        .is_none_or(|category| &quirk.category == category)
+                        && !picked.iter().any(|p| {
+                            p.name.eq_ignore_ascii_case(&quirk.name)
+                                || (constraints.one_per_category && p.category == quirk.category)
+                        })
+                })
+                .collect();
+            let Ok(entry) = candidates.choose_weighted(rng, |entry| entry.weight) else {
+                break;
+            };
+            picked.push(entry.quirk.clone());
+        }
+        picked
+    }
+}
+
+/// A rough classification of a boon or flaw, used to weigh them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EffectKind {
+    /// Mentions advantage or disadvantage, e.g. "disadvantage on Will
+    /// vs fear". Weighs [`EffectKind::ADVANTAGE_WEIGHT`].
+    Advantage,
+    /// A flat modifier such as "+1 Power". Weighs
+    /// [`EffectKind::MODIFIER_WEIGHT`].
+    Modifier,
+    /// Anything else. Weighs [`EffectKind::NARRATIVE_WEIGHT`].
+    Narrative,
+}
+
+impl EffectKind {
+    pub const ADVANTAGE_WEIGHT: usize = 3;
+    pub const MODIFIER_WEIGHT: usize = 2;
+    pub const NARRATIVE_WEIGHT: usize = 1;
+
+    /// Classifies the text of a boon or flaw.
+    ///
+    /// Mentions of advantage take precedence over flat modifiers.
+    #[must_use]
+    pub fn classify(text: &str) -> EffectKind {
+        let text = text.to_lowercase();
+        if text.contains("advantage") {
+            EffectKind::Advantage
+        } else if text
+            .as_bytes()
+            .windows(2)
+            .any(|pair| matches!(pair[0], b'+' | b'-') && pair[1].is_ascii_digit())
+        {
+            EffectKind::Modifier
+        } else {
+            EffectKind::Narrative
+        }
+    }
+
+    #[must_use]
+    pub fn weight(self) -> usize {
+        match self {
+            EffectKind::Advantage => Self::ADVANTAGE_WEIGHT,
+            EffectKind::Modifier => Self::MODIFIER_WEIGHT,
+            EffectKind::Narrative => Self::NARRATIVE_WEIGHT,
+        }
+    }
+}
+
+/// A passive stat modifier read from a boon or flaw, such as
+/// "+1 quantity to Forte while you carry a shield" or
+/// "disadvantage on Will vs fear".
+///
+/// The grammar, ignoring case, is either of
+///
+/// ```text
+/// {+|-}{n} [quantity|quality] [to] {stat} [{keyword} {condition}]
+/// [{n}] {advantage|disadvantage} [on|to|for] {stat} [{keyword} {condition}]
+/// ```
+///
+/// where `keyword` is `while`, `when`, `if`, `vs`, `versus`, or
+/// `against`.
+///
+/// A flat modifier is to quantity unless it says `quality`, in which
+/// case it shifts tiers, positive being better. Advantage and
+/// disadvantage are one level unless a count is given. Without a
+/// condition a modifier always applies. A condition is kept as
+/// written after its keyword, minus a trailing period, for
+/// [`crate::Character::passive_stat_with`] to evaluate.
+/// [`crate::Character::passive_stat`] understands carrying an item:
+/// `you carry a {item}`, `carrying {item}`, and `you have {item}`,
+/// with an optional `a`, `an`, or `the`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PassiveModifier {
+    pub stat: String,
+    pub quality_shift: i8,
+    pub quantity_delta: isize,
+    /// Levels of advantage on rolls of the stat.
+    pub advantage: usize,
+    /// Levels of disadvantage on rolls of the stat.
+    pub disadvantage: usize,
+    pub condition: Option<String>,
+}
+
+impl PassiveModifier {
+    /// Reads a modifier from the text of a boon or flaw, or `None`
+    /// if it isn't one.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<PassiveModifier> {
+        let text = text.trim().trim_end_matches('.');
+        Self::parse_flat(text).or_else(|| Self::parse_rolled(text))
+    }
+
+    /// Reads a `+1 quantity to Forte` style modifier.
+    fn parse_flat(text: &str) -> Option<PassiveModifier> {
+        let (amount, rest) = text.split_once(char::is_whitespace)?;
+        let delta: isize = amount
+            .strip_prefix('+')
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .map(str::parse)
+            .or_else(|| {
+                amount
+                    .strip_prefix('-')
+                    .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+                    .map(|digits| digits.parse::<isize>().map(|n| -n))
+            })?
+            .ok()?;
+
+        let mut rest = rest.trim_start();
+        let mut is_quality = false;
+        for (word, quality) in [("quantity", false), ("quality", true)] {
+            if let Some(after) = strip_word(rest, word) {
+                rest = after;
+                is_quality = quality;
+                break;
+            }
+        }
+        rest = strip_word(rest, "to").unwrap_or(rest);
+        let (stat, condition) = split_condition(rest)?;
+
+        Some(PassiveModifier {
+            stat,
+            quality_shift: if is_quality {
+                i8::try_from(delta).unwrap_or(if delta < 0 { i8::MIN } else { i8::MAX })
+            } else {
+                0
+            },
+            quantity_delta: if is_quality { 0 } else { delta },
+            advantage: 0,
+            disadvantage: 0,
+            condition,
+        })
+    }
+
+    /// Reads a `2 disadvantage on Will vs fear` style modifier.
+    fn parse_rolled(text: &str) -> Option<PassiveModifier> {
+        let mut rest = text;
+        let mut levels = 1;
+        if let Some((count, after)) = text.split_once(char::is_whitespace) {
+            if let Ok(count) = count.parse() {
+                levels = count;
+                rest = after.trim_start();
+            }
+        }
+        let (advantage, disadvantage, rest) = if let Some(after) = strip_word(rest, "advantage") {
+            (levels, 0, after)
+        } else if let Some(after) = strip_word(rest, "disadvantage") {
+            (0, levels, after)
+        } else {
+            return None;
+        };
+        let prepositions = ["on", "to", "for"];
+        if prepositions
+            .iter()
+            .any(|word| rest.eq_ignore_ascii_case(word))
+        {
+            return None;
+        }
+        let rest = prepositions
+            .iter()
+            .find_map(|word| strip_word(rest, word))
+            .unwrap_or(rest);
+        let (stat, condition) = split_condition(rest)?;
+
+        Some(PassiveModifier {
+            stat,
+            quality_shift: 0,
+            quantity_delta: 0,
+            advantage,
+            disadvantage,
+            condition,
+        })
+    }
+}
+
+/// Splits `{stat} [{keyword} {condition}]`, or `None` if either part
+/// is empty.
+fn split_condition(text: &str) -> Option<(String, Option<String>)> {
+    let lower = text.to_ascii_lowercase();
+    let split = [" while ", " when ", " if ", " vs ", " versus ", " against "]
+        .iter()
+        .filter_map(|keyword| lower.find(keyword).map(|index| (index, keyword.len())))
+        .min();
+    let (stat, condition) = match split {
+        Some((index, len)) => (&text[..index], Some(text[index + len..].trim())),
+        None => (text, None),
+    };
+    let stat = stat.trim();
+    if stat.is_empty() || condition.is_some_and(str::is_empty) {
+        return None;
+    }
+    Some((stat.to_string(), condition.map(str::to_string)))
+}
+
+/// Strips a leading word and the whitespace after it, ignoring case.
+fn strip_word<'a>(text: &'a str, word: &str) -> Option<&'a str> {
+    let (first, rest) = text.split_once(char::is_whitespace)?;
+    first.eq_ignore_ascii_case(word).then(|| rest.trim_start())
+}
+
+/// How a quirk's boons weigh against its flaws.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct QuirkBalance {
+    pub name: String,
+    pub boons: usize,
+    pub flaws: usize,
+    pub boon_weight: usize,
+    pub flaw_weight: usize,
+}
+
+impl QuirkBalance {
+    /// Boon weight minus flaw weight. Positive favors the player.
+    #[must_use]
+    pub fn net(&self) -> isize {
+        isize::try_from(self.boon_weight).unwrap_or(isize::MAX)
+            - isize::try_from(self.flaw_weight).unwrap_or(isize::MAX)
+    }
+}
+
+impl core::fmt::Display for QuirkBalance {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}: {} boon(s) weighing {}, {} flaw(s) weighing {}, net {:+}",
+            self.name,
+            self.boons,
+            self.boon_weight,
+            self.flaws,
+            self.flaw_weight,
+            self.net()
+        )
+    }
+}
+
+/// The balance of every quirk on a character.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct QuirkBalanceReport {
+    pub quirks: Vec<QuirkBalance>,
+}
+
+impl QuirkBalanceReport {
+    /// The sum of every quirk's net weight.
+    #[must_use]
+    pub fn net(&self) -> isize {
+        self.quirks.iter().map(QuirkBalance::net).sum()
+    }
+}
+
+impl core::fmt::Display for QuirkBalanceReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for quirk in &self.quirks {
+            writeln!(f, "{quirk}")?;
+        }
+        write!(f, "Total: net {:+}", self.net())
+    }
+}
+
+/// The changes between two versions of a quirk, from [`Quirk::diff`].
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct QuirkDiff {
+    /// The name of the newer version.
+    pub name: String,
+    /// The old and new category, if it changed.
+    pub category: Option<(QuirkCategory, QuirkCategory)>,
+    pub description_changed: bool,
+    pub added_boons: Vec<String>,
+    pub removed_boons: Vec<String>,
+    pub added_flaws: Vec<String>,
+    pub removed_flaws: Vec<String>,
+}
+
+impl QuirkDiff {
+    /// Whether the two versions had the same category,
+    /// description, boons, and flaws.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.category.is_none()
+            && !self.description_changed
+            && self.added_boons.is_empty()
+            && self.removed_boons.is_empty()
+            && self.added_flaws.is_empty()
+            && self.removed_flaws.is_empty()
+    }
+}
+
+impl core::fmt::Display for QuirkDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:", self.name)?;
+        if self.is_empty() {
+            return write!(f, " unchanged");
+        }
+        if let Some((old, new)) = &self.category {
+            write!(f, "\n  category: {old} -> {new}")?;
+        }
+        if self.description_changed {
+            write!(f, "\n  description changed")?;
+        }
+        for boon in &self.added_boons {
+            write!(f, "\n  + boon: {boon}")?;
+        }
+        for boon in &self.removed_boons {
+            write!(f, "\n  - boon: {boon}")?;
+        }
+        for flaw in &self.added_flaws {
+            write!(f, "\n  + flaw: {flaw}")?;
+        }
+        for flaw in &self.removed_flaws {
+            write!(f, "\n  - flaw: {flaw}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How often one quirk was used this session.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct QuirkUsage {
+    pub name: String,
+    pub uses: usize,
+    pub notes: Vec<String>,
+}
+
+/// How often each of a character's quirks was used this session,
+/// most used first.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SessionQuirkReport {
+    pub quirks: Vec<QuirkUsage>,
+}
+
+impl core::fmt::Display for SessionQuirkReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, usage) in self.quirks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {} use(s)", usage.name, usage.uses)?;
+            for note in &usage.notes {
+                write!(f, "\n  - {note}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A requirement a character must meet to take a quirk.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Prerequisite {
+    /// A stat or skill of at least the given quality and quantity,
+    /// e.g. `Power` at A2 or better.
+    MinStat(String, Quality, usize),
+    /// A specific stock.
+    Stock(String),
+    /// Another quirk, by name.
+    HasQuirk(String),
+}
+
+impl core::fmt::Display for Prerequisite {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Prerequisite::MinStat(name, quality, quantity) => {
+                let stat = crate::Stat {
+                    name: String::new(),
+                    quality: *quality,
+                    quantity: *quantity,
+                    checks: None,
+                };
+                write!(f, "{name} {stat}+")
+            }
+            Prerequisite::Stock(stock) => write!(f, "stock {stock}"),
+            Prerequisite::HasQuirk(quirk) => write!(f, "quirk {quirk}"),
+        }
+    }
+}
+
+/// A [`Prerequisite`] a character doesn't meet.
+#[derive(Clone, PartialEq, Debug)]
+pub struct UnmetPrerequisite {
+    pub prerequisite: Prerequisite,
+}
+
+impl core::fmt::Display for UnmetPrerequisite {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "requires {}", self.prerequisite)
+    }
+}
+
 /// The Quirk category.
-#[derive(Encode, Decode, Serialize, Deserialize, Clone, PartialEq, Debug)]
+///
+/// Deserializes case-insensitively, so legacy files with
+/// lowercase categories like `"ethos"` load.
+///
+/// Categories are ordered canonically: Ethos, Pathos, Logos,
+/// then Uncategorized.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub enum QuirkCategory {
     Ethos,
     Pathos,
@@ -42,8 +995,111 @@ pub enum QuirkCategory {
     Uncategorized,
 }
 
-impl std::fmt::Display for QuirkCategory {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl QuirkCategory {
+    /// Every category, in canonical order.
+    pub const ALL: [QuirkCategory; 4] = [
+        QuirkCategory::Ethos,
+        QuirkCategory::Pathos,
+        QuirkCategory::Logos,
+        QuirkCategory::Uncategorized,
+    ];
+
+    /// Iterates over every category, in canonical order.
+    pub fn iter() -> impl Iterator<Item = QuirkCategory> {
+        Self::ALL.into_iter()
+    }
+}
+
+impl FromStr for QuirkCategory {
+    type Err = QuirkParseError;
+
+    /// Parses a category name, ignoring case and surrounding whitespace.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ethos" => Ok(QuirkCategory::Ethos),
+            "pathos" => Ok(QuirkCategory::Pathos),
+            "logos" => Ok(QuirkCategory::Logos),
+            "uncategorized" => Ok(QuirkCategory::Uncategorized),
+            _ => Err(QuirkParseError::UnknownCategory(s.trim().to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+const CATEGORY_VARIANTS: &[&str] = &["Ethos", "Pathos", "Logos", "Uncategorized"];
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for QuirkCategory {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CategoryVisitor;
+
+        impl<'de> de::Visitor<'de> for CategoryVisitor {
+            type Value = QuirkCategory;
+
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                write!(f, "a quirk category")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<QuirkCategory, E> {
+                v.parse()
+                    .map_err(|_| E::unknown_variant(v, CATEGORY_VARIANTS))
+            }
+
+            fn visit_enum<A: de::EnumAccess<'de>>(
+                self,
+                data: A,
+            ) -> Result<QuirkCategory, A::Error> {
+                let (CategoryName(category), variant) = data.variant()?;
+                de::VariantAccess::unit_variant(variant)?;
+                Ok(category)
+            }
+        }
+
+        /// A variant name, matched case-insensitively.
+        struct CategoryName(QuirkCategory);
+
+        impl<'de> Deserialize<'de> for CategoryName {
+            fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct NameVisitor;
+
+                impl de::Visitor<'_> for NameVisitor {
+                    type Value = CategoryName;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        write!(f, "a quirk category name")
+                    }
+
+                    fn visit_str<E: de::Error>(self, v: &str) -> Result<CategoryName, E> {
+                        CategoryVisitor.visit_str(v).map(CategoryName)
+                    }
+
+                    fn visit_u64<E: de::Error>(self, v: u64) -> Result<CategoryName, E> {
+                        let category = match v {
+                            0 => QuirkCategory::Ethos,
+                            1 => QuirkCategory::Pathos,
+                            2 => QuirkCategory::Logos,
+                            3 => QuirkCategory::Uncategorized,
+                            _ => {
+                                return Err(E::invalid_value(
+                                    de::Unexpected::Unsigned(v),
+                                    &"a variant index 0 <= i < 4",
+                                ))
+                            }
+                        };
+                        Ok(CategoryName(category))
+                    }
+                }
+
+                deserializer.deserialize_identifier(NameVisitor)
+            }
+        }
+
+        deserializer.deserialize_enum("QuirkCategory", CATEGORY_VARIANTS, CategoryVisitor)
+    }
+}
+
+impl core::fmt::Display for QuirkCategory {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             QuirkCategory::Ethos => write!(f, "Ethos"),
             QuirkCategory::Pathos => write!(f, "Pathos"),
@@ -53,7 +1109,67 @@ impl std::fmt::Display for QuirkCategory {
     }
 }
 
-#[derive(Encode, Decode, Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+/// An error produced by [`Quirk::merge`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuirkMergeError {
+    /// The quirks have different names.
+    NameMismatch(String, String),
+    /// The quirks have the same name but different categories.
+    CategoryMismatch(QuirkCategory, QuirkCategory),
+}
+
+impl core::fmt::Display for QuirkMergeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QuirkMergeError::NameMismatch(a, b) => {
+                write!(f, "can't merge differently named quirks `{a}` and `{b}`")
+            }
+            QuirkMergeError::CategoryMismatch(a, b) => {
+                write!(
+                    f,
+                    "can't merge quirks in different categories ({a} and {b})"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for QuirkMergeError {}
+
+/// An error produced while parsing a quirk from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuirkParseError {
+    /// The text contained no quirk.
+    Empty,
+    /// The quirk had no name.
+    MissingName,
+    /// A `**` around the name was never closed.
+    UnclosedBold,
+    /// A `(` around the category was never closed.
+    UnclosedCategory,
+    /// The category was not one of the known categories.
+    UnknownCategory(String),
+}
+
+impl core::fmt::Display for QuirkParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QuirkParseError::Empty => write!(f, "no quirk found"),
+            QuirkParseError::MissingName => write!(f, "quirk has no name"),
+            QuirkParseError::UnclosedBold => write!(f, "unclosed `**` around quirk name"),
+            QuirkParseError::UnclosedCategory => write!(f, "unclosed `(` around quirk category"),
+            QuirkParseError::UnknownCategory(category) => {
+                write!(f, "unknown quirk category `{category}`")
+            }
+        }
+    }
+}
+
+impl core::error::Error for QuirkParseError {}
+
+#[derive(Default, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Inspiration {
     pub ethos: usize,
     pub pathos: usize,
@@ -65,4 +1181,971 @@ impl Inspiration {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// The inspiration in a category's pool.
+    ///
+    /// Uncategorized quirks have no pool, so this is always
+    /// zero for [`QuirkCategory::Uncategorized`].
+    #[must_use]
+    pub fn get(&self, category: &QuirkCategory) -> usize {
+        inspiration_field(category).map_or(0, |field| self.field(field))
+    }
+
+    /// The pool for a category, for modifying in place.
+    ///
+    /// Returns `None` for [`QuirkCategory::Uncategorized`], which
+    /// has no pool, so awards for it are ignored:
+    ///
+    /// ```text
+    /// if let Some(pool) = inspiration.get_mut(&quirk.category) {
+    ///     *pool += 1;
+    /// }
+    /// ```
+    pub fn get_mut(&mut self, category: &QuirkCategory) -> Option<&mut usize> {
+        inspiration_field(category).map(|field| self.field_mut(field))
+    }
+
+    /// The inspiration in a pool.
+    #[must_use]
+    pub fn field(&self, field: InspirationField) -> usize {
+        match field {
+            InspirationField::Ethos => self.ethos,
+            InspirationField::Pathos => self.pathos,
+            InspirationField::Logos => self.logos,
+        }
+    }
+
+    /// A pool, for modifying in place.
+    pub fn field_mut(&mut self, field: InspirationField) -> &mut usize {
+        match field {
+            InspirationField::Ethos => &mut self.ethos,
+            InspirationField::Pathos => &mut self.pathos,
+            InspirationField::Logos => &mut self.logos,
+        }
+    }
+
+    /// Awards inspiration in a category unless it would overflow.
+    ///
+    /// Returns the new total, or `None`, changing nothing, on
+    /// overflow or for a category with no pool.
+    pub fn checked_award(&mut self, category: &QuirkCategory, amount: usize) -> Option<usize> {
+        let pool = self.get_mut(category)?;
+        *pool = pool.checked_add(amount)?;
+        Some(*pool)
+    }
+
+    /// Spends up to `amount` inspiration in a category, stopping at zero.
+    ///
+    /// Returns how much was actually spent.
+    pub fn saturating_spend(&mut self, category: &QuirkCategory, amount: usize) -> usize {
+        let Some(pool) = self.get_mut(category) else {
+            return 0;
+        };
+        let spent = amount.min(*pool);
+        *pool -= spent;
+        spent
+    }
+
+    /// A copy with every pool capped at `cap`.
+    #[must_use]
+    pub fn clamped_to(&self, cap: usize) -> Inspiration {
+        Inspiration {
+            ethos: self.ethos.min(cap),
+            pathos: self.pathos.min(cap),
+            logos: self.logos.min(cap),
+        }
+    }
+}
+
+/// Serializes [`Inspiration`] as a map from lowercased category
+/// names to pool sizes, like `{"ethos": 1, "pathos": 0, "logos": 2}`.
+/// Use with `#[serde(with = "quirk::inspiration_map")]`.
+///
+/// Unlike the derived struct form, this is a map in every format,
+/// so tools that don't know the struct can read it. Keys are matched
+/// ignoring case, and missing categories are zero.
+#[cfg(feature = "serde")]
+pub mod inspiration_map {
+    use alloc::collections::BTreeMap;
+
+    use serde::{de, ser::SerializeMap, Deserialize, Deserializer, Serializer};
+
+    use super::{inspiration_field, Inspiration, InspirationField, QuirkCategory};
+
+    /// Serializes each category's pool under its lowercased name.
+    ///
+    /// # Errors
+    ///
+    /// Returns the serializer's error, if any.
+    pub fn serialize<S: Serializer>(
+        inspiration: &Inspiration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        for field in InspirationField::ALL {
+            map.serialize_entry(
+                &field.category().to_string().to_ascii_lowercase(),
+                &inspiration.field(field),
+            )?;
+        }
+        map.end()
+    }
+
+    /// Deserializes a map of category names to pool sizes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for a key that isn't a category with a pool.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Inspiration, D::Error> {
+        let mut inspiration = Inspiration::new();
+        for (key, value) in BTreeMap::<String, usize>::deserialize(deserializer)? {
+            let category: QuirkCategory = key.parse().map_err(de::Error::custom)?;
+            let field = inspiration_field(&category)
+                .ok_or_else(|| de::Error::custom(format!("`{key}` has no inspiration pool")))?;
+            *inspiration.field_mut(field) = value;
+        }
+        Ok(inspiration)
+    }
+}
+
+impl Add for Inspiration {
+    type Output = Inspiration;
+
+    /// Adds each pool, saturating at `usize::MAX`.
+    fn add(self, other: Inspiration) -> Inspiration {
+        Inspiration {
+            ethos: self.ethos.saturating_add(other.ethos),
+            pathos: self.pathos.saturating_add(other.pathos),
+            logos: self.logos.saturating_add(other.logos),
+        }
+    }
+}
+
+impl Sub for Inspiration {
+    type Output = Inspiration;
+
+    /// Subtracts each pool, stopping at zero since inspiration
+    /// can't go negative. Costs a pool can't cover are dropped
+    /// rather than taken from another pool.
+    fn sub(self, other: Inspiration) -> Inspiration {
+        Inspiration {
+            ethos: self.ethos.saturating_sub(other.ethos),
+            pathos: self.pathos.saturating_sub(other.pathos),
+            logos: self.logos.saturating_sub(other.logos),
+        }
+    }
+}
+
+impl AddAssign for Inspiration {
+    /// See [`Add`].
+    fn add_assign(&mut self, other: Inspiration) {
+        *self = core::mem::take(self) + other;
+    }
+}
+
+impl SubAssign for Inspiration {
+    /// See [`Sub`]; each pool stops at zero.
+    fn sub_assign(&mut self, other: Inspiration) {
+        *self = core::mem::take(self) - other;
+    }
+}
+
+/// One of the three inspiration pools on an [`Inspiration`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InspirationField {
+    Ethos,
+    Pathos,
+    Logos,
+}
+
+impl InspirationField {
+    /// Every pool, in display order.
+    pub const ALL: [InspirationField; 3] = [
+        InspirationField::Ethos,
+        InspirationField::Pathos,
+        InspirationField::Logos,
+    ];
+
+    /// The quirk category whose inspiration goes in this pool.
+    #[must_use]
+    pub fn category(self) -> QuirkCategory {
+        match self {
+            InspirationField::Ethos => QuirkCategory::Ethos,
+            InspirationField::Pathos => QuirkCategory::Pathos,
+            InspirationField::Logos => QuirkCategory::Logos,
+        }
+    }
+}
+
+/// The inspiration pool for a quirk category.
+///
+/// This is the one place [`QuirkCategory::Uncategorized`], which has
+/// no pool, is mapped to `None`; every inspiration helper goes through it.
+#[must_use]
+pub fn inspiration_field(category: &QuirkCategory) -> Option<InspirationField> {
+    match category {
+        QuirkCategory::Ethos => Some(InspirationField::Ethos),
+        QuirkCategory::Pathos => Some(InspirationField::Pathos),
+        QuirkCategory::Logos => Some(InspirationField::Logos),
+        QuirkCategory::Uncategorized => None,
+    }
+}
+
+/// Why inspiration was awarded or spent, supplied by the caller.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InspirationNote {
+    pub reason: String,
+    /// A timestamp or session label, in whatever form the caller likes.
+    pub session: String,
+}
+
+impl InspirationNote {
+    #[must_use]
+    pub fn new(reason: String, session: String) -> Self {
+        Self { reason, session }
+    }
+}
+
+/// A recorded change to a character's inspiration.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "bitcode", derive(Encode, Decode))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InspirationEvent {
+    pub category: QuirkCategory,
+    /// Positive when awarded, negative when spent.
+    pub delta: isize,
+    pub reason: String,
+    pub session: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passive_modifier_reads_advantage_and_disadvantage() {
+        let modifier = PassiveModifier::parse("Disadvantage on Will vs fear.").unwrap();
+        assert_eq!(
+            modifier,
+            PassiveModifier {
+                stat: "Will".into(),
+                quality_shift: 0,
+                quantity_delta: 0,
+                advantage: 0,
+                disadvantage: 1,
+                condition: Some("fear".into()),
+            }
+        );
+
+        let modifier = PassiveModifier::parse("2 advantage to Speed").unwrap();
+        assert_eq!((modifier.advantage, modifier.disadvantage), (2, 0));
+        assert_eq!(modifier.condition, None);
+
+        assert_eq!(PassiveModifier::parse("Advantage on"), None);
+        assert_eq!(PassiveModifier::parse("Has the advantage"), None);
+    }
+
+    #[test]
+    fn passive_modifier_still_reads_flat_modifiers() {
+        let modifier =
+            PassiveModifier::parse("+1 quantity to Forte while you carry a shield").unwrap();
+        assert_eq!(modifier.stat, "Forte");
+        assert_eq!(modifier.quantity_delta, 1);
+        assert_eq!((modifier.advantage, modifier.disadvantage), (0, 0));
+        assert_eq!(modifier.condition.as_deref(), Some("you carry a shield"));
+    }
+
+    fn named(name: &str, category: QuirkCategory, description: &str) -> Quirk {
+        Quirk {
+            category,
+            description: description.into(),
+            ..Quirk::new(name.into())
+        }
+    }
+
+    #[test]
+    fn cmp_for_display_orders_by_category_then_name() {
+        let ethos = named("Zealous", QuirkCategory::Ethos, "");
+        let logos = named("Analytical", QuirkCategory::Logos, "");
+        assert_eq!(ethos.cmp_for_display(&logos), Ordering::Less);
+
+        let lower = named("brave", QuirkCategory::Pathos, "");
+        let upper = named("Brave", QuirkCategory::Pathos, "");
+        let later = named("Calm", QuirkCategory::Pathos, "");
+        assert_eq!(lower.cmp_for_display(&upper), Ordering::Equal);
+        assert_eq!(upper.cmp_for_display(&later), Ordering::Less);
+    }
+
+    #[test]
+    fn from_text_block_reads_a_prep_document() {
+        let quirks =
+            Quirk::parse_many(include_str!("../tests/fixtures/quirks/prep_doc.md")).unwrap();
+        assert_eq!(quirks.len(), 2);
+
+        let coward = &quirks[0];
+        assert_eq!(coward.name, "Coward");
+        assert_eq!(coward.category, QuirkCategory::Pathos);
+        assert_eq!(coward.description, "Runs from danger.");
+        assert_eq!(coward.boons, ["Hard to ambush"]);
+        assert_eq!(coward.flaws, ["Disadvantage on Will vs fear"]);
+
+        let bookworm = &quirks[1];
+        assert_eq!(bookworm.category, QuirkCategory::Logos);
+        // The unrecognized trailing line folds into the description.
+        assert_eq!(
+            bookworm.description,
+            "Always reading. Spends every coin on books."
+        );
+        assert_eq!(
+            bookworm.boons,
+            ["+1 quantity to Conscious", "Knows obscure lore"]
+        );
+        assert_eq!(bookworm.flaws, ["Near-sighted"]);
+    }
+
+    #[test]
+    fn from_text_block_reads_a_bulleted_wiki_list() {
+        let quirks =
+            Quirk::parse_many(include_str!("../tests/fixtures/quirks/wiki_list.txt")).unwrap();
+        assert_eq!(quirks.len(), 2);
+        assert_eq!(quirks[0].name, "Oathbound");
+        assert_eq!(quirks[0].category, QuirkCategory::Ethos);
+        assert_eq!(quirks[0].boons, ["Advantage on Will while keeping an oath"]);
+        assert_eq!(quirks[0].flaws, ["Can't lie"]);
+        assert_eq!(quirks[1].name, "Night Owl");
+        assert_eq!(quirks[1].category, QuirkCategory::Uncategorized);
+        assert_eq!(quirks[1].description, "Sees well in the dark.");
+        assert_eq!(quirks[1].flaws, ["Sluggish before noon"]);
+    }
+
+    #[test]
+    fn from_text_block_reads_plain_notes() {
+        let quirk =
+            Quirk::from_text_block(include_str!("../tests/fixtures/quirks/plain_notes.txt"))
+                .unwrap();
+        assert_eq!(quirk.name, "Wanderer");
+        assert_eq!(quirk.description, "Never stays in one place for long.");
+        assert_eq!(quirk.boons, ["Always knows the road"]);
+        assert!(quirk.flaws.is_empty());
+    }
+
+    #[test]
+    fn from_text_block_rejects_malformed_blocks() {
+        assert!(matches!(
+            Quirk::from_text_block(" \n "),
+            Err(QuirkParseError::Empty)
+        ));
+        assert!(matches!(
+            Quirk::from_text_block("**Coward (Pathos)"),
+            Err(QuirkParseError::UnclosedBold)
+        ));
+        assert!(matches!(
+            Quirk::from_text_block("Coward (Pathos: Runs"),
+            Err(QuirkParseError::UnclosedCategory)
+        ));
+        assert!(matches!(
+            Quirk::from_text_block(": Runs from danger"),
+            Err(QuirkParseError::MissingName)
+        ));
+        assert!(matches!(
+            Quirk::from_text_block("Coward (Bravado): Runs"),
+            Err(QuirkParseError::UnknownCategory(category)) if category == "Bravado"
+        ));
+    }
+
+    #[test]
+    fn uncategorized_inspiration_reads_zero() {
+        let inspiration = Inspiration {
+            ethos: 1,
+            pathos: 2,
+            logos: 3,
+        };
+        assert_eq!(inspiration.get(&QuirkCategory::Ethos), 1);
+        assert_eq!(inspiration.get(&QuirkCategory::Pathos), 2);
+        assert_eq!(inspiration.get(&QuirkCategory::Logos), 3);
+        assert_eq!(inspiration.get(&QuirkCategory::Uncategorized), 0);
+    }
+
+    #[test]
+    fn uncategorized_inspiration_awards_are_ignored() {
+        let mut inspiration = Inspiration::new();
+        assert!(inspiration.get_mut(&QuirkCategory::Uncategorized).is_none());
+        assert_eq!(
+            inspiration.checked_award(&QuirkCategory::Uncategorized, 1),
+            None
+        );
+        assert_eq!(
+            inspiration.saturating_spend(&QuirkCategory::Uncategorized, 1),
+            0
+        );
+        assert_eq!(inspiration, Inspiration::new());
+
+        for category in [
+            QuirkCategory::Ethos,
+            QuirkCategory::Pathos,
+            QuirkCategory::Logos,
+        ] {
+            *inspiration.get_mut(&category).unwrap() += 1;
+            assert_eq!(inspiration.get(&category), 1);
+        }
+        assert_eq!(
+            inspiration,
+            Inspiration {
+                ethos: 1,
+                pathos: 1,
+                logos: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn only_uncategorized_has_no_inspiration_field() {
+        assert_eq!(
+            inspiration_field(&QuirkCategory::Ethos),
+            Some(InspirationField::Ethos)
+        );
+        assert_eq!(
+            inspiration_field(&QuirkCategory::Pathos),
+            Some(InspirationField::Pathos)
+        );
+        assert_eq!(
+            inspiration_field(&QuirkCategory::Logos),
+            Some(InspirationField::Logos)
+        );
+        assert_eq!(inspiration_field(&QuirkCategory::Uncategorized), None);
+    }
+
+    #[test]
+    fn checked_award_stops_at_the_cap_of_usize() {
+        let mut inspiration = Inspiration {
+            ethos: usize::MAX - 1,
+            ..Inspiration::new()
+        };
+        assert_eq!(
+            inspiration.checked_award(&QuirkCategory::Ethos, 1),
+            Some(usize::MAX)
+        );
+        assert_eq!(inspiration.checked_award(&QuirkCategory::Ethos, 1), None);
+        assert_eq!(inspiration.ethos, usize::MAX);
+        assert_eq!(
+            inspiration.checked_award(&QuirkCategory::Pathos, 0),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn saturating_spend_stops_at_zero() {
+        let mut inspiration = Inspiration {
+            logos: 2,
+            ..Inspiration::new()
+        };
+        assert_eq!(inspiration.saturating_spend(&QuirkCategory::Logos, 1), 1);
+        assert_eq!(inspiration.saturating_spend(&QuirkCategory::Logos, 5), 1);
+        assert_eq!(inspiration.logos, 0);
+        assert_eq!(inspiration.saturating_spend(&QuirkCategory::Logos, 1), 0);
+        assert_eq!(inspiration.logos, 0);
+    }
+
+    #[test]
+    fn clamped_to_caps_each_pool() {
+        let inspiration = Inspiration {
+            ethos: 0,
+            pathos: 3,
+            logos: 5,
+        };
+        assert_eq!(
+            inspiration.clamped_to(3),
+            Inspiration {
+                ethos: 0,
+                pathos: 3,
+                logos: 3,
+            }
+        );
+        assert_eq!(inspiration.clamped_to(0), Inspiration::new());
+        assert_eq!(inspiration.clamped_to(usize::MAX), inspiration);
+    }
+
+    #[test]
+    fn inspiration_arithmetic_saturates() {
+        let full = Inspiration {
+            ethos: usize::MAX,
+            pathos: 1,
+            logos: 0,
+        };
+        let one = Inspiration {
+            ethos: 1,
+            pathos: 1,
+            logos: 1,
+        };
+        assert_eq!(
+            full.clone() + one.clone(),
+            Inspiration {
+                ethos: usize::MAX,
+                pathos: 2,
+                logos: 1,
+            }
+        );
+        assert_eq!(
+            full.clone() - one.clone(),
+            Inspiration {
+                ethos: usize::MAX - 1,
+                pathos: 0,
+                logos: 0,
+            }
+        );
+        let mut total = full;
+        total += one.clone();
+        total -= one.clone();
+        total -= one;
+        assert_eq!(
+            total,
+            Inspiration {
+                ethos: usize::MAX - 2,
+                pathos: 0,
+                logos: 0,
+            }
+        );
+    }
+
+    #[cfg(feature = "std")]
+    fn library() -> QuirkLibrary {
+        let mut library = QuirkLibrary::new();
+        for (name, category, weight) in [
+            ("Stubborn", QuirkCategory::Ethos, 3),
+            ("Honest", QuirkCategory::Ethos, 1),
+            ("Hot-Headed", QuirkCategory::Pathos, 2),
+            ("Bookish", QuirkCategory::Logos, 1),
+            ("Forgotten", QuirkCategory::Logos, 0),
+        ] {
+            library.quirks.push(LibraryEntry {
+                quirk: named(name, category, ""),
+                weight,
+            });
+        }
+        library
+    }
+
+    #[cfg(feature = "std")]
+    fn names(quirks: &[Quirk]) -> Vec<&str> {
+        quirks.iter().map(|quirk| quirk.name.as_str()).collect()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn seeded_random_picks_are_reproducible() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let library = library();
+        let mut rng = StdRng::seed_from_u64(7);
+        let picks: Vec<String> = (0..8)
+            .map(|_| library.random(&mut rng, None).unwrap().name)
+            .collect();
+        assert_eq!(
+            picks,
+            [
+                "Stubborn",
+                "Stubborn",
+                "Stubborn",
+                "Stubborn",
+                "Stubborn",
+                "Stubborn",
+                "Hot-Headed",
+                "Honest",
+            ]
+        );
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let set = library.random_set(&mut rng, 3, RandomSetConstraints::default());
+        assert_eq!(names(&set), ["Stubborn", "Honest", "Hot-Headed"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn random_never_picks_zero_weights_or_other_categories() {
+        let library = library();
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            assert_eq!(
+                library
+                    .random(&mut rng, Some(&QuirkCategory::Logos))
+                    .unwrap()
+                    .name,
+                "Bookish"
+            );
+        }
+        assert_eq!(
+            library.random(&mut rng, Some(&QuirkCategory::Uncategorized)),
+            None
+        );
+        assert_eq!(QuirkLibrary::new().random(&mut rng, None), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn random_set_has_no_duplicates_and_respects_categories() {
+        let library = library();
+        let mut rng = rand::thread_rng();
+        for _ in 0..50 {
+            let set = library.random_set(&mut rng, 10, RandomSetConstraints::default());
+            let mut picked = names(&set);
+            picked.sort_unstable();
+            assert_eq!(picked, ["Bookish", "Honest", "Hot-Headed", "Stubborn"]);
+
+            let set = library.random_set(
+                &mut rng,
+                10,
+                RandomSetConstraints {
+                    one_per_category: true,
+                    ..RandomSetConstraints::default()
+                },
+            );
+            let categories: Vec<&QuirkCategory> = set.iter().map(|quirk| &quirk.category).collect();
+            assert_eq!(set.len(), 3);
+            assert!(categories.contains(&&QuirkCategory::Ethos));
+            assert!(categories.contains(&&QuirkCategory::Pathos));
+            assert!(categories.contains(&&QuirkCategory::Logos));
+
+            let set = library.random_set(
+                &mut rng,
+                10,
+                RandomSetConstraints {
+                    category: Some(&QuirkCategory::Ethos),
+                    ..RandomSetConstraints::default()
+                },
+            );
+            let mut picked = names(&set);
+            picked.sort_unstable();
+            assert_eq!(picked, ["Honest", "Stubborn"]);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn library_weights_default_to_one() {
+        let library = QuirkLibrary::from_json(
+            r#"{"quirks": [
+                {"name": "Stubborn", "category": "Ethos", "description": "", "boons": [], "flaws": [], "weight": 5},
+                {"name": "Honest", "category": "Ethos", "description": "", "boons": [], "flaws": []}
+            ]}"#,
+        )
+        .unwrap();
+        let weights: Vec<u32> = library.quirks.iter().map(|entry| entry.weight).collect();
+        assert_eq!(weights, [5, 1]);
+    }
+
+    #[test]
+    fn categories_iterate_and_sort_canonically() {
+        let all: Vec<QuirkCategory> = QuirkCategory::iter().collect();
+        assert_eq!(all, QuirkCategory::ALL);
+
+        let mut shuffled = vec![
+            QuirkCategory::Uncategorized,
+            QuirkCategory::Logos,
+            QuirkCategory::Ethos,
+            QuirkCategory::Pathos,
+        ];
+        shuffled.sort();
+        assert_eq!(shuffled, QuirkCategory::ALL);
+        assert!(QuirkCategory::Ethos < QuirkCategory::Pathos);
+        assert!(QuirkCategory::Logos < QuirkCategory::Uncategorized);
+    }
+
+    #[test]
+    fn categories_group_in_canonical_order() {
+        let mut groups: alloc::collections::BTreeMap<QuirkCategory, Vec<&str>> =
+            alloc::collections::BTreeMap::new();
+        for (name, category) in [
+            ("Odd", QuirkCategory::Uncategorized),
+            ("Bookish", QuirkCategory::Logos),
+            ("Stubborn", QuirkCategory::Ethos),
+            ("Honest", QuirkCategory::Ethos),
+        ] {
+            groups.entry(category).or_default().push(name);
+        }
+        let keys: Vec<&QuirkCategory> = groups.keys().collect();
+        assert_eq!(
+            keys,
+            [
+                &QuirkCategory::Ethos,
+                &QuirkCategory::Logos,
+                &QuirkCategory::Uncategorized
+            ]
+        );
+        assert_eq!(groups[&QuirkCategory::Ethos], ["Stubborn", "Honest"]);
+    }
+
+    fn revised() -> (Quirk, Quirk) {
+        let old = Quirk {
+            boons: vec!["Advantage on Will.".into(), "Keeps watch.".into()],
+            flaws: vec!["Never retreats.".into()],
+            ..named("Stubborn", QuirkCategory::Ethos, "Won't budge.")
+        };
+        let new = Quirk {
+            boons: vec!["Keeps watch.".into(), "+1 Forte.".into()],
+            flaws: vec!["Never retreats.".into()],
+            ..named("Stubborn", QuirkCategory::Pathos, "Won't budge an inch.")
+        };
+        (old, new)
+    }
+
+    #[test]
+    fn diff_lists_every_change() {
+        let (old, new) = revised();
+        assert_eq!(
+            old.diff(&new),
+            QuirkDiff {
+                name: "Stubborn".into(),
+                category: Some((QuirkCategory::Ethos, QuirkCategory::Pathos)),
+                description_changed: true,
+                added_boons: vec!["+1 Forte.".into()],
+                removed_boons: vec!["Advantage on Will.".into()],
+                added_flaws: Vec::new(),
+                removed_flaws: Vec::new(),
+            }
+        );
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn diff_display() {
+        let (old, new) = revised();
+        assert_eq!(
+            old.diff(&new).to_string(),
+            "Stubborn:\n  category: Ethos -> Pathos\n  description changed\n  + boon: +1 Forte.\n  - boon: Advantage on Will."
+        );
+        assert_eq!(old.diff(&old).to_string(), "Stubborn: unchanged");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn diff_round_trips_through_json() {
+        let (old, new) = revised();
+        crate::test_support::assert_json_roundtrip(&old.diff(&new));
+    }
+
+    #[test]
+    fn effect_weights_are_pinned() {
+        assert_eq!(EffectKind::ADVANTAGE_WEIGHT, 3);
+        assert_eq!(EffectKind::MODIFIER_WEIGHT, 2);
+        assert_eq!(EffectKind::NARRATIVE_WEIGHT, 1);
+        for (text, kind) in [
+            ("Advantage on Will.", EffectKind::Advantage),
+            ("Disadvantage on Power vs fear.", EffectKind::Advantage),
+            ("+1 advantage to Speed", EffectKind::Advantage),
+            ("+1 Forte.", EffectKind::Modifier),
+            ("-2 quality to Power", EffectKind::Modifier),
+            ("Can't resist a bet.", EffectKind::Narrative),
+            ("Rank 3 of the guild.", EffectKind::Narrative),
+        ] {
+            assert_eq!(EffectKind::classify(text), kind, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn balance_weighs_each_kind_of_effect() {
+        let quirk = Quirk {
+            boons: vec!["Advantage on Will.".into(), "+1 Forte.".into()],
+            flaws: vec!["Can't resist a bet.".into()],
+            ..Quirk::new("Gambler".into())
+        };
+        let balance = quirk.balance();
+        assert_eq!(
+            balance,
+            QuirkBalance {
+                name: "Gambler".into(),
+                boons: 2,
+                flaws: 1,
+                boon_weight: 5,
+                flaw_weight: 1,
+            }
+        );
+        assert_eq!(balance.net(), 4);
+        assert_eq!(
+            balance.to_string(),
+            "Gambler: 2 boon(s) weighing 5, 1 flaw(s) weighing 1, net +4"
+        );
+        assert_eq!(
+            Quirk::new("Plain".into()).balance().to_string(),
+            "Plain: 0 boon(s) weighing 0, 0 flaw(s) weighing 0, net +0"
+        );
+    }
+
+    #[test]
+    fn merging_unions_effects_and_joins_descriptions() {
+        let mut quirk = Quirk {
+            description: "Won't back down.".into(),
+            boons: vec!["Advantage on Will.".into()],
+            flaws: vec!["Pride".into()],
+            prerequisites: vec![Prerequisite::Stock("Human".into())],
+            tags: vec!["social".into()],
+            uses_this_session: 1,
+            session_notes: vec!["Refused the duke.".into()],
+            ..Quirk::new("Stubborn".into())
+        };
+        quirk
+            .merge(Quirk {
+                description: "Ever.".into(),
+                boons: vec!["Advantage on Will.".into(), "+1 Forte.".into()],
+                flaws: vec!["Pride".into()],
+                prerequisites: vec![
+                    Prerequisite::Stock("Human".into()),
+                    Prerequisite::HasQuirk("Honest".into()),
+                ],
+                tags: vec!["Social".into(), "core".into()],
+                uses_this_session: 2,
+                session_notes: vec!["Held the gate.".into()],
+                ..Quirk::new("stubborn".into())
+            })
+            .unwrap();
+
+        assert_eq!(quirk.name, "Stubborn");
+        assert_eq!(quirk.description, "Won't back down.\n\nEver.");
+        assert_eq!(quirk.boons, ["Advantage on Will.", "+1 Forte."]);
+        assert_eq!(quirk.flaws, ["Pride"]);
+        assert_eq!(
+            quirk.prerequisites,
+            [
+                Prerequisite::Stock("Human".into()),
+                Prerequisite::HasQuirk("Honest".into()),
+            ]
+        );
+        assert_eq!(quirk.tags, ["social", "core"]);
+        assert_eq!(quirk.uses_this_session, 3);
+        assert_eq!(quirk.session_notes, ["Refused the duke.", "Held the gate."]);
+    }
+
+    #[test]
+    fn merging_keeps_one_copy_of_a_description() {
+        let described = |description: &str| Quirk {
+            description: description.into(),
+            ..Quirk::new("Stubborn".into())
+        };
+        for (ours, theirs, merged) in [
+            ("", "Ever.", "Ever."),
+            ("Ever.", "", "Ever."),
+            ("Ever.", "Ever.", "Ever."),
+        ] {
+            let mut quirk = described(ours);
+            quirk.merge(described(theirs)).unwrap();
+            assert_eq!(quirk.description, merged);
+        }
+    }
+
+    #[test]
+    fn merging_mismatched_quirks_fails_untouched() {
+        let mut quirk = Quirk::new("Stubborn".into());
+        let original = quirk.clone();
+        assert_eq!(
+            quirk.merge(Quirk::new("Honest".into())),
+            Err(QuirkMergeError::NameMismatch(
+                "Stubborn".into(),
+                "Honest".into()
+            ))
+        );
+        assert_eq!(
+            quirk.merge(Quirk {
+                category: QuirkCategory::Logos,
+                boons: vec!["+1 Forte.".into()],
+                ..Quirk::new("Stubborn".into())
+            }),
+            Err(QuirkMergeError::CategoryMismatch(
+                QuirkCategory::Ethos,
+                QuirkCategory::Logos
+            ))
+        );
+        assert_eq!(quirk, original);
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Pools {
+        #[serde(with = "inspiration_map")]
+        inspiration: Inspiration,
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn inspiration_maps_round_trip() {
+        let pools = Pools {
+            inspiration: Inspiration {
+                ethos: 1,
+                pathos: 0,
+                logos: 2,
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&pools).unwrap(),
+            r#"{"inspiration":{"ethos":1,"pathos":0,"logos":2}}"#
+        );
+        crate::test_support::assert_json_roundtrip(&pools);
+
+        let loaded: Pools = serde_json::from_str(r#"{"inspiration":{"LOGOS":3}}"#).unwrap();
+        assert_eq!(
+            loaded.inspiration,
+            Inspiration {
+                ethos: 0,
+                pathos: 0,
+                logos: 3,
+            }
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn inspiration_maps_reject_keys_without_a_pool() {
+        for json in [
+            r#"{"inspiration":{"uncategorized":1}}"#,
+            r#"{"inspiration":{"mythos":1}}"#,
+            r#"{"inspiration":{"ethos":-1}}"#,
+            r#"{"inspiration":[1,0,2]}"#,
+        ] {
+            assert!(serde_json::from_str::<Pools>(json).is_err(), "{json}");
+        }
+        let error = serde_json::from_str::<Pools>(r#"{"inspiration":{"uncategorized":1}}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("`uncategorized` has no inspiration pool"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn quirk_markdown_snapshot() {
+        let quirk = Quirk {
+            description: "Acts_first,\\ asks <later>.".into(),
+            boons: vec!["Advantage on Will.".into()],
+            flaws: vec!["`-1` Speed.".into(), "#1 fan".into()],
+            ..named("*Bold* [x]|y", QuirkCategory::Pathos, "")
+        };
+        assert_eq!(
+            quirk.to_markdown(9),
+            r#"<a id="bold-xy"></a>
+###### \*Bold\* \[x\]\|y
+
+`Pathos`
+
+Acts\_first,\\ asks \<later\>.
+
+**Boons**
+
+- Advantage on Will.
+
+**Flaws**
+
+- \`-1\` Speed.
+- \#1 fan
+"#
+        );
+        assert_eq!(
+            Quirk::new("Plain".into()).to_markdown(0),
+            "<a id=\"plain\"></a>\n# Plain\n\n`Ethos`\n"
+        );
+    }
 }
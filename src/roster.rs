@@ -0,0 +1,223 @@
+//! # Roster
+//! Many characters saved together, such as a campaign's whole cast.
+//!
+//! A roster stream is one [envelope](crate::serialization::envelope)
+//! per character, back to back, so each record carries its own
+//! length and CRC. Streams are written and read one character at a
+//! time, so even huge rosters never need to fit in memory at once.
+
+use alloc::vec::Vec;
+use std::io::{BufRead, Write};
+
+use crate::serialization::envelope::{
+    read_envelope, write_envelope, EnvelopeError, EnvelopeMeta, PayloadEncoding,
+};
+use crate::{ArrataError, Character};
+
+/// A list of characters.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Roster {
+    pub characters: Vec<Character>,
+}
+
+impl Roster {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes characters to `writer` as a roster stream, encoding one
+    /// at a time. Returns how many were written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EnvelopeError::Io`] if writing fails, or
+    /// [`EnvelopeError::PayloadTooLarge`] if a character encodes to
+    /// over 4 GiB. Characters before the failure have been written.
+    pub fn write_stream<'a>(
+        writer: &mut impl Write,
+        characters: impl IntoIterator<Item = &'a Character>,
+    ) -> Result<usize, EnvelopeError> {
+        let mut written = 0;
+        for character in characters {
+            write_envelope(
+                writer,
+                EnvelopeMeta::new(PayloadEncoding::Bitcode),
+                &character.encode_saved(),
+            )?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Lazily reads a roster stream from `reader`, one character per
+    /// item, in order.
+    ///
+    /// A record that's intact but corrupt, failing its CRC or not
+    /// decoding, is yielded as an error and reading moves on to the
+    /// next. A record whose header is damaged or cut short loses the
+    /// place of every record after it, so its error is the last item.
+    pub fn read_stream(
+        mut reader: impl BufRead,
+    ) -> impl Iterator<Item = Result<Character, DecodeError>> {
+        let mut index = 0;
+        let mut done = false;
+        core::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let result = match reader.fill_buf() {
+                // Streams end cleanly between records.
+                Ok([]) => return None,
+                Ok(_) => match read_envelope(&mut reader) {
+                    Ok((meta, payload)) => Character::from_envelope_payload(meta, &payload),
+                    Err(error) => {
+                        done = !matches!(error, EnvelopeError::CrcMismatch { .. });
+                        Err(error.into())
+                    }
+                },
+                Err(error) => {
+                    done = true;
+                    Err(EnvelopeError::Io(error).into())
+                }
+            };
+            let item = result.map_err(|error| DecodeError { index, error });
+            index += 1;
+            Some(item)
+        })
+    }
+
+    /// Reads a whole roster stream, skipping records that can't be
+    /// read. Returns the roster and the errors for skipped records.
+    ///
+    /// See [`Roster::read_stream`] for reading one at a time.
+    pub fn from_stream(reader: impl BufRead) -> (Roster, Vec<DecodeError>) {
+        let mut roster = Roster::new();
+        let mut errors = Vec::new();
+        for result in Self::read_stream(reader) {
+            match result {
+                Ok(character) => roster.characters.push(character),
+                Err(error) => errors.push(error),
+            }
+        }
+        (roster, errors)
+    }
+}
+
+/// A record in a roster stream couldn't be read.
+#[derive(Debug)]
+pub struct DecodeError {
+    /// The record's position in the stream, from 0.
+    pub index: usize,
+    pub error: ArrataError,
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "roster record {}: {}", self.index, self.error)
+    }
+}
+
+impl core::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{self, BufReader, Read};
+
+    use crate::serialization::envelope::HEADER_LEN;
+
+    fn cast() -> Vec<Character> {
+        ["Ash", "Bo", "Cy"]
+            .into_iter()
+            .map(|name| Character::new(name.into()))
+            .collect()
+    }
+
+    fn stream(characters: &[Character]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        assert_eq!(
+            Roster::write_stream(&mut bytes, characters).unwrap(),
+            characters.len()
+        );
+        bytes
+    }
+
+    /// Fails every read, to show nothing past it was asked for.
+    struct Unreadable;
+
+    impl Read for Unreadable {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("read past the first record"))
+        }
+    }
+
+    #[test]
+    fn streams_round_trip() {
+        let cast = cast();
+        let (roster, errors) = Roster::from_stream(stream(&cast).as_slice());
+        assert!(errors.is_empty());
+        assert_eq!(roster.characters, cast);
+    }
+
+    #[test]
+    fn empty_streams_have_no_records() {
+        assert_eq!(Roster::read_stream(&[][..]).count(), 0);
+    }
+
+    #[test]
+    fn records_are_read_one_at_a_time() {
+        let cast = cast();
+        let first = stream(&cast[..1]);
+        let mut records = Roster::read_stream(BufReader::new(first.as_slice().chain(Unreadable)));
+
+        assert_eq!(records.next().unwrap().unwrap(), cast[0]);
+        let error = records.next().unwrap().unwrap_err();
+        assert_eq!(error.index, 1);
+        assert!(matches!(
+            error.error,
+            ArrataError::Envelope(EnvelopeError::Io(_))
+        ));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn corrupt_records_are_skipped() {
+        let cast = cast();
+        let mut bytes = stream(&cast);
+        let first_len = stream(&cast[..1]).len();
+        bytes[first_len + HEADER_LEN] ^= 0xff;
+
+        let records: Vec<_> = Roster::read_stream(bytes.as_slice()).collect();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].as_ref().unwrap(), &cast[0]);
+        let error = records[1].as_ref().unwrap_err();
+        assert_eq!(error.index, 1);
+        assert!(matches!(
+            error.error,
+            ArrataError::Envelope(EnvelopeError::CrcMismatch { .. })
+        ));
+        assert_eq!(records[2].as_ref().unwrap(), &cast[2]);
+
+        let (roster, errors) = Roster::from_stream(bytes.as_slice());
+        assert_eq!(roster.characters, [cast[0].clone(), cast[2].clone()]);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn truncated_records_end_the_stream() {
+        let cast = cast();
+        let mut bytes = stream(&cast);
+        bytes.truncate(bytes.len() - 1);
+
+        let records: Vec<_> = Roster::read_stream(bytes.as_slice()).collect();
+        assert_eq!(records.len(), 3);
+        assert!(records[..2].iter().all(Result::is_ok));
+        assert_eq!(records[2].as_ref().unwrap_err().index, 2);
+    }
+}
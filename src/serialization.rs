@@ -0,0 +1,4 @@
+//! # Serialization
+//! Shared plumbing for the crate's binary save formats.
+
+pub mod envelope;
@@ -0,0 +1,416 @@
+//! # Envelope
+//! A small versioned header wrapped around every binary save.
+//!
+//! Binary encodings like bitcode aren't self-describing, so a save
+//! can't be read back without knowing how it was written. The
+//! envelope records that up front, and a checksum catches saves
+//! that were truncated or corrupted along the way.
+//!
+//! ## Layout
+//!
+//! All integers are little-endian.
+//!
+//! | Bytes  | Field    | Notes                                      |
+//! |--------|----------|--------------------------------------------|
+//! | 0..4   | magic    | [`MAGIC`], `ARRT`                          |
+//! | 4..6   | version  | [`FORMAT_VERSION`] when written            |
+//! | 6      | encoding | A [`PayloadEncoding`]                      |
+//! | 7      | reserved | Always 0                                   |
+//! | 8..10  | flags    | Reserved for future use, written as 0      |
+//! | 10..14 | length   | The payload's length in bytes              |
+//! | 14..18 | CRC      | The CRC-32 (IEEE) of the payload           |
+//! | 18..   | payload  |                                            |
+//!
+//! Version 1 is frozen: any change to this layout or to the layout
+//! of a payload must bump [`FORMAT_VERSION`].
+
+use alloc::vec::Vec;
+use std::io::{self, Read, Write};
+
+/// The first four bytes of every envelope.
+pub const MAGIC: [u8; 4] = *b"ARRT";
+
+/// The envelope version this build writes, and the newest it reads.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The size of the header before the payload, in bytes.
+pub const HEADER_LEN: usize = 18;
+
+/// How an envelope's payload is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PayloadEncoding {
+    Bitcode = 0,
+    Json = 1,
+}
+
+impl PayloadEncoding {
+    /// The encoding with the given id, if known.
+    #[must_use]
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(PayloadEncoding::Bitcode),
+            1 => Some(PayloadEncoding::Json),
+            _ => None,
+        }
+    }
+
+    /// The id stored in the header.
+    #[must_use]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+}
+
+/// Everything in an envelope's header besides its length and CRC,
+/// which are derived from the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EnvelopeMeta {
+    /// The layout version, from 1 to [`FORMAT_VERSION`].
+    pub version: u16,
+    pub encoding: PayloadEncoding,
+    /// Reserved for future use. Preserved when read.
+    pub flags: u16,
+}
+
+impl EnvelopeMeta {
+    /// The header for a payload written by this build.
+    #[must_use]
+    pub fn new(encoding: PayloadEncoding) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            encoding,
+            flags: 0,
+        }
+    }
+}
+
+/// An envelope couldn't be written or read.
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// The data didn't start with [`MAGIC`], so isn't an envelope.
+    BadMagic([u8; 4]),
+    /// The version is 0 or newer than [`FORMAT_VERSION`].
+    UnknownVersion(u16),
+    /// The encoding id isn't a [`PayloadEncoding`].
+    UnknownEncoding(u8),
+    /// The payload was a different length than the header says.
+    LengthMismatch { expected: usize, actual: usize },
+    /// The payload's CRC didn't match the header, so it's corrupt.
+    CrcMismatch { expected: u32, actual: u32 },
+    /// The payload is too long for the header's 32-bit length.
+    PayloadTooLarge(usize),
+    /// Reading or writing failed.
+    Io(io::Error),
+}
+
+impl core::fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            EnvelopeError::BadMagic(magic) => write!(f, "not an Arrata save (magic {magic:02x?})"),
+            EnvelopeError::UnknownVersion(version) => {
+                write!(f, "unknown save version {version}")
+            }
+            EnvelopeError::UnknownEncoding(id) => write!(f, "unknown save encoding {id}"),
+            EnvelopeError::LengthMismatch { expected, actual } => write!(
+                f,
+                "save payload is {actual} bytes, but the header says {expected}"
+            ),
+            EnvelopeError::CrcMismatch { expected, actual } => write!(
+                f,
+                "save is corrupt: CRC {actual:08x}, but the header says {expected:08x}"
+            ),
+            EnvelopeError::PayloadTooLarge(len) => {
+                write!(f, "save payload of {len} bytes is too large")
+            }
+            EnvelopeError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for EnvelopeError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            EnvelopeError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for EnvelopeError {
+    fn from(error: io::Error) -> Self {
+        EnvelopeError::Io(error)
+    }
+}
+
+/// Writes `payload` to `writer` inside an envelope.
+///
+/// # Errors
+///
+/// Returns [`EnvelopeError::UnknownVersion`] if `meta.version` isn't
+/// one this build can read back, [`EnvelopeError::PayloadTooLarge`]
+/// if `payload` is over 4 GiB, or [`EnvelopeError::Io`] if writing
+/// fails.
+pub fn write_envelope(
+    writer: &mut impl Write,
+    meta: EnvelopeMeta,
+    payload: &[u8],
+) -> Result<(), EnvelopeError> {
+    check_version(meta.version)?;
+    let length =
+        u32::try_from(payload.len()).map_err(|_| EnvelopeError::PayloadTooLarge(payload.len()))?;
+
+    let mut header = [0; HEADER_LEN];
+    header[0..4].copy_from_slice(&MAGIC);
+    header[4..6].copy_from_slice(&meta.version.to_le_bytes());
+    header[6] = meta.encoding.id();
+    header[8..10].copy_from_slice(&meta.flags.to_le_bytes());
+    header[10..14].copy_from_slice(&length.to_le_bytes());
+    header[14..18].copy_from_slice(&crc32(payload).to_le_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    Ok(())
+}
+
+/// Reads an envelope from `reader`, returning its header and
+/// checked payload.
+///
+/// Exactly one envelope is read, so several can be read back to
+/// back from the same reader.
+///
+/// # Errors
+///
+/// Returns the [`EnvelopeError`] for the first problem found, in the
+/// order the header is laid out. A reader that ends inside the header
+/// is an [`EnvelopeError::Io`], and one that ends inside the payload
+/// is an [`EnvelopeError::LengthMismatch`].
+pub fn read_envelope(reader: &mut impl Read) -> Result<(EnvelopeMeta, Vec<u8>), EnvelopeError> {
+    let mut header = [0; HEADER_LEN];
+    reader.read_exact(&mut header)?;
+    let field = |at: usize| [header[at], header[at + 1]];
+    let word = |at: usize| [header[at], header[at + 1], header[at + 2], header[at + 3]];
+
+    let magic = word(0);
+    if magic != MAGIC {
+        return Err(EnvelopeError::BadMagic(magic));
+    }
+    let version = u16::from_le_bytes(field(4));
+    check_version(version)?;
+    let encoding =
+        PayloadEncoding::from_id(header[6]).ok_or(EnvelopeError::UnknownEncoding(header[6]))?;
+    let flags = u16::from_le_bytes(field(8));
+    let expected = usize::try_from(u32::from_le_bytes(word(10))).unwrap_or(usize::MAX);
+    let crc = u32::from_le_bytes(word(14));
+
+    // Read through `take`, rather than allocating `expected` bytes
+    // up front, so a corrupt length can't force a huge allocation.
+    let mut payload = Vec::new();
+    reader
+        .take(u64::try_from(expected).unwrap_or(u64::MAX))
+        .read_to_end(&mut payload)?;
+    if payload.len() != expected {
+        return Err(EnvelopeError::LengthMismatch {
+            expected,
+            actual: payload.len(),
+        });
+    }
+    let actual = crc32(&payload);
+    if actual != crc {
+        return Err(EnvelopeError::CrcMismatch {
+            expected: crc,
+            actual,
+        });
+    }
+
+    Ok((
+        EnvelopeMeta {
+            version,
+            encoding,
+            flags,
+        },
+        payload,
+    ))
+}
+
+fn check_version(version: u16) -> Result<(), EnvelopeError> {
+    if (1..=FORMAT_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(EnvelopeError::UnknownVersion(version))
+    }
+}
+
+/// The lookup table for [`crc32`], one entry per byte value.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        #[allow(clippy::cast_possible_truncation)]
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+};
+
+/// The CRC-32 used by zlib and PNG.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, byte| {
+        CRC_TABLE[usize::from(crc.to_le_bytes()[0] ^ byte)] ^ (crc >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The CRC-32 check value, from the standard `123456789` input.
+    const PAYLOAD: &[u8] = b"123456789";
+
+    const GOLDEN: [u8; HEADER_LEN] = [
+        b'A', b'R', b'R', b'T', // magic
+        0x01, 0x00, // version 1
+        0x00, // bitcode
+        0x00, // reserved
+        0x00, 0x00, // flags
+        0x09, 0x00, 0x00, 0x00, // length 9
+        0x26, 0x39, 0xf4, 0xcb, // CRC 0xcbf43926
+    ];
+
+    fn golden() -> Vec<u8> {
+        [&GOLDEN[..], PAYLOAD].concat()
+    }
+
+    #[test]
+    fn crc32_matches_the_check_value() {
+        assert_eq!(crc32(PAYLOAD), 0xcbf4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn version_1_headers_are_frozen() {
+        let mut bytes = Vec::new();
+        write_envelope(
+            &mut bytes,
+            EnvelopeMeta::new(PayloadEncoding::Bitcode),
+            PAYLOAD,
+        )
+        .unwrap();
+        assert_eq!(bytes, golden());
+
+        let (meta, payload) = read_envelope(&mut golden().as_slice()).unwrap();
+        assert_eq!(meta, EnvelopeMeta::new(PayloadEncoding::Bitcode));
+        assert_eq!(payload, PAYLOAD);
+    }
+
+    #[test]
+    fn json_headers_and_flags_are_frozen() {
+        let meta = EnvelopeMeta {
+            flags: 0x0201,
+            ..EnvelopeMeta::new(PayloadEncoding::Json)
+        };
+        let mut bytes = Vec::new();
+        write_envelope(&mut bytes, meta, PAYLOAD).unwrap();
+        assert_eq!(bytes[6], 0x01);
+        assert_eq!(bytes[8..10], [0x01, 0x02]);
+        assert_eq!(read_envelope(&mut bytes.as_slice()).unwrap().0, meta);
+    }
+
+    #[test]
+    fn bad_magic_is_rejected() {
+        let mut bytes = golden();
+        bytes[0..4].copy_from_slice(b"PK\x03\x04");
+        assert!(matches!(
+            read_envelope(&mut bytes.as_slice()),
+            Err(EnvelopeError::BadMagic(magic)) if magic == *b"PK\x03\x04"
+        ));
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        for version in [0, FORMAT_VERSION + 1, u16::MAX] {
+            let mut bytes = golden();
+            bytes[4..6].copy_from_slice(&version.to_le_bytes());
+            assert!(matches!(
+                read_envelope(&mut bytes.as_slice()),
+                Err(EnvelopeError::UnknownVersion(v)) if v == version
+            ));
+            let meta = EnvelopeMeta {
+                version,
+                ..EnvelopeMeta::new(PayloadEncoding::Bitcode)
+            };
+            assert!(matches!(
+                write_envelope(&mut Vec::new(), meta, PAYLOAD),
+                Err(EnvelopeError::UnknownVersion(v)) if v == version
+            ));
+        }
+    }
+
+    #[test]
+    fn unknown_encodings_are_rejected() {
+        let mut bytes = golden();
+        bytes[6] = 7;
+        assert!(matches!(
+            read_envelope(&mut bytes.as_slice()),
+            Err(EnvelopeError::UnknownEncoding(7))
+        ));
+    }
+
+    #[test]
+    fn short_payloads_are_length_mismatches() {
+        let bytes = golden();
+        assert!(matches!(
+            read_envelope(&mut &bytes[..bytes.len() - 2]),
+            Err(EnvelopeError::LengthMismatch {
+                expected: 9,
+                actual: 7
+            })
+        ));
+
+        let mut bytes = golden();
+        bytes[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            read_envelope(&mut bytes.as_slice()),
+            Err(EnvelopeError::LengthMismatch { actual: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn corrupt_payloads_are_crc_mismatches() {
+        let mut bytes = golden();
+        bytes[HEADER_LEN] ^= 0x01;
+        assert!(matches!(
+            read_envelope(&mut bytes.as_slice()),
+            Err(EnvelopeError::CrcMismatch {
+                expected: 0xcbf4_3926,
+                actual
+            }) if actual == crc32(b"023456789")
+        ));
+    }
+
+    #[test]
+    fn truncated_headers_are_io_errors() {
+        assert!(matches!(
+            read_envelope(&mut &GOLDEN[..HEADER_LEN - 1]),
+            Err(EnvelopeError::Io(error)) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn envelopes_read_back_to_back() {
+        let mut bytes = golden();
+        write_envelope(&mut bytes, EnvelopeMeta::new(PayloadEncoding::Json), b"{}").unwrap();
+        let mut reader = bytes.as_slice();
+        assert_eq!(read_envelope(&mut reader).unwrap().1, PAYLOAD);
+        assert_eq!(read_envelope(&mut reader).unwrap().1, b"{}");
+        assert!(reader.is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! # Test Support
+//! Round-trip assertions for applications' own test suites.
+//!
+//! Each helper checks a value survives a save format unchanged and
+//! panics with both values if it doesn't, like `assert_eq!`.
+//!
+//! The crate's own tests use these too, so each helper is also built
+//! in test builds that have its format.
+
+use core::fmt::Debug;
+
+#[cfg(feature = "bitcode")]
+use bitcode::{DecodeOwned, Encode};
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(feature = "bitcode")]
+use crate::Character;
+
+/// Asserts `value` survives a trip through JSON.
+///
+/// Fields skipped when saving, like `Character::temp_effects`, don't
+/// survive, so leave them empty.
+///
+/// # Panics
+///
+/// Panics if `value` can't be saved or loaded, or loads as a
+/// different value.
+#[cfg(feature = "serde")]
+pub fn assert_json_roundtrip<T: Serialize + DeserializeOwned + PartialEq + Debug>(value: &T) {
+    let json = serde_json::to_string(value).expect("value should serialize to JSON");
+    let back: T = serde_json::from_str(&json).expect("JSON should deserialize");
+    assert_eq!(&back, value, "JSON round trip changed the value: {json}");
+}
+
+/// Asserts `value` survives a trip through bitcode.
+///
+/// # Panics
+///
+/// Panics if the encoded value can't be decoded, or decodes as a
+/// different value.
+#[cfg(feature = "bitcode")]
+pub fn assert_bitcode_roundtrip<T: Encode + DecodeOwned + PartialEq + Debug>(value: &T) {
+    let bytes = bitcode::encode(value);
+    let back: T = bitcode::decode(&bytes).expect("bitcode should decode");
+    assert_eq!(&back, value, "bitcode round trip changed the value");
+}
+
+/// Asserts `character` survives [`Character::to_envelope_bytes`] and
+/// [`Character::from_envelope_bytes`].
+///
+/// Envelopes leave out `Character::temp_effects` too, so leave them
+/// empty.
+///
+/// # Panics
+///
+/// Panics if the character can't be saved or loaded, or loads as a
+/// different character.
+#[cfg(feature = "bitcode")]
+pub fn assert_envelope_roundtrip(character: &Character) {
+    let bytes = character
+        .to_envelope_bytes()
+        .expect("character should fit in an envelope");
+    let back = Character::from_envelope_bytes(&bytes).expect("envelope should load");
+    assert_eq!(
+        &back, character,
+        "envelope round trip changed the character"
+    );
+}
@@ -0,0 +1,37 @@
+//! Builds only without the `serde` feature, so the gate catches core
+//! types or dice math that start depending on it.
+
+#![cfg(not(feature = "serde"))]
+
+use arrata_lib::{roll_stat_with_rng, success_probability, Obstacle, Quality, Stat};
+use rand::rngs::mock::StepRng;
+
+fn b4() -> Stat {
+    Stat {
+        quality: "B".parse().unwrap(),
+        quantity: 4,
+        ..Stat::new("Will".into())
+    }
+}
+
+#[test]
+fn core_types_work_without_serde() {
+    let stat = b4();
+    assert_eq!(stat.quality, Quality::Basic);
+    assert_eq!(format!("{stat}"), "B4");
+    assert_eq!("Ob2".parse(), Ok(Obstacle::new(2)));
+}
+
+#[test]
+fn dice_roll_without_serde() {
+    let result = roll_stat_with_rng(&b4(), 0, 0, &mut StepRng::new(0, 1));
+    assert_eq!(result.results.len(), 4);
+    assert!(success_probability(&b4(), 0, 0, &Obstacle::new(1)) > 0.9);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn thread_rng_rolls_without_serde() {
+    let result = arrata_lib::roll_stat(&b4(), 1, 0);
+    assert!(result.results.len() >= 4);
+}